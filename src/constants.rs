@@ -2,3 +2,13 @@ pub(crate) const OFFCHAIN_TIMESTAMP_DETAILS: &str = "off_chain_timestamp_details
 pub(crate) const OFF_CHAIN_METRICS_FOR_VALIDATOR: &str = "off_chain_metrics_for_validator";
 pub(crate) const OFF_CHAIN_STATE: &str = "off_chain_state";
 pub(crate) const OFF_CHAIN_VALIDATOR_IDX_MAPPING: &str = "off_chain_validator_idx_mapping";
+pub(crate) const VALIDATORS: &str = "validators";
+pub(crate) const VALIDATORS_ACCOUNT_IDX: &str = "validators__account";
+pub(crate) const VALIDATORS_INSERTION_ORDER_IDX: &str = "validators__insertion_order";
+pub(crate) const SLASHING_EVENTS: &str = "slashing_events";
+pub(crate) const SLASHING_EVENTS_VALIDATOR_IDX: &str = "slashing_events__validator";
+pub(crate) const COMMISSION_CHANGES: &str = "commission_changes";
+pub(crate) const COMMISSION_CHANGES_VALIDATOR_IDX: &str = "commission_changes__validator";
+
+pub(crate) const DEFAULT_LIMIT: u32 = 10;
+pub(crate) const MAX_LIMIT: u32 = 30;
@@ -1,18 +1,33 @@
+use crate::constants::{DEFAULT_LIMIT, MAX_LIMIT};
 use crate::conversion_utils;
 use crate::error::ContractError;
 use crate::msg::{
-    ExecuteMsg, InstantiateMsg, MigrateMsg, OffChainTimestamps, OffChainValidators, QueryMsg,
-    ValidatorAprResponse,
+    AprWindow, AprWindowsResponse, CommissionChangeResponse, CronStatusResponse, ExecuteMsg,
+    InstantiateMsg, MigrateMsg, OffChainTimestamps, OffChainValidators, PendingUnbondingsResponse,
+    QueryMsg, RoleGrant, SlashingAdjustedAprResponse, TimestampAggregateResponse,
+    TimestampResolution, ValidatorAprResponse, ValidatorScore, ValidatorVotingPower,
+    VotingPowerRecord,
 };
-use crate::state::ValidatorAccounts;
+use crate::scoring::{compute_scores, total_weight, ScoreInputs};
 use crate::state::{
-    Config, OffChainState, OffChainValidatorMetrics, OffchainTimestampMetaData, State,
-    ValidatorMetrics, CONFIG, METRICS_HISTORY, OFF_CHAIN_STATE, OFF_CHAIN_STATE_FOR_VALIDATOR,
-    OFF_CHAIN_TIMESTAMPS, OFF_CHAIN_TIMESTAMP_META_DATA, OFF_CHAIN_VALIDATOR_IDX_MAPPING, STATE,
+    commission_changes, default_commission_change_threshold, default_unbonding_period,
+    slashing_events, validators, CommissionChange, CronRecord, Role, SkippedValidator,
+    SlashingEvent, TimestampAggregate, UnbondingEntry, ValidatorAccounts, ValidatorStatus,
+    ValidatorStatusRecord, CRON_RECORDS, ROLES, STATUS_HISTORY, TIMESTAMP_AGGREGATES, UNBONDINGS,
+    WITHDRAWAL_RECIPIENTS,
+};
+use crate::state::{
+    Config, CronSchedule, KeeperIncentive, OffChainState, OffChainValidatorMetrics,
+    OffchainTimestampMetaData, PendingManager, RewardsBaseline, ScoreWeights, State,
+    ValidatorMetrics, CONFIG, LEGACY_STATE, METRICS_HISTORY, OFF_CHAIN_STATE,
+    OFF_CHAIN_STATE_FOR_VALIDATOR, OFF_CHAIN_TIMESTAMPS, OFF_CHAIN_TIMESTAMP_META_DATA,
+    OFF_CHAIN_VALIDATOR_IDX_MAPPING, REWARDS_BASELINES, STATE,
 };
 use crate::util::{
-    compute_apr, decimal_division_in_256, decimal_multiplication_in_256, decimal_summation_in_256,
-    uint128_to_decimal,
+    compute_apr, compute_gross_apr, compute_median, compute_principal_lost_to_slashing,
+    compute_slashing_adjusted_apr, compute_time_weighted_commission, decimal_division_in_256,
+    decimal_multiplication_in_256, decimal_subtraction_in_256, decimal_summation_in_256,
+    u64_to_decimal, uint128_to_decimal,
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
@@ -20,10 +35,9 @@ use cosmwasm_std::{
     to_binary, Addr, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Order, Response, StakingMsg,
     StdError, StdResult, Storage, Uint128,
 };
-use cosmwasm_std::{BankMsg, Decimal};
-use cw_storage_plus::{Bound, U16Key, U64Key};
-use std::cmp;
-use std::cmp::min;
+use cosmwasm_std::{BankMsg, CosmosMsg, Decimal, DistributionMsg, Event};
+use cw_storage_plus::{Bound, PrimaryKey, U16Key, U64Key};
+use std::cmp::{max, min};
 use std::collections::HashMap;
 use std::ops::Sub;
 use terra_cosmwasm::{TerraMsgWrapper, TerraQuerier};
@@ -39,14 +53,24 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     let state = State {
         vault_denom: msg.vault_denom.clone(),
-        validators: vec![],
         cron_timestamps: vec![],
         validator_index_for_next_cron: 0,
+        next_validator_insertion_order: 0,
+        total_validators: 0,
+        next_unbonding_id: 0,
+        total_unbonding: Uint128::zero(),
     };
     let config = Config {
         manager: info.sender.clone(),
         amount_to_stake_per_validator: msg.amount_to_stake_per_validator,
         batch_size: msg.batch_size,
+        pending_manager: None,
+        score_weights: ScoreWeights::default(),
+        commission_change_threshold: default_commission_change_threshold(),
+        refuse_new_cron_when_incomplete: false,
+        cron_schedule: None,
+        keeper_incentive: None,
+        unbonding_period: default_unbonding_period(),
     };
 
     STATE.save(deps.storage, &state)?;
@@ -78,6 +102,7 @@ pub fn migrate(
 ) -> Result<Response<TerraMsgWrapper>, ContractError> {
     CONFIG.update(_deps.storage, |mut conf| -> StdResult<_> {
         conf.manager = _msg.manager_address.clone();
+        conf.pending_manager = None;
         Ok(conf)
     })?;
 
@@ -88,9 +113,40 @@ pub fn migrate(
 
     OFF_CHAIN_STATE.save(_deps.storage, &off_chain_state)?;
 
+    let validators_migrated = migrate_validators_to_registry(_deps.storage)?;
+
     Ok(Response::new()
         .add_attribute("method", "update_config")
-        .add_attribute("new_manager", _msg.manager_address.to_string()))
+        .add_attribute("new_manager", _msg.manager_address.to_string())
+        .add_attribute("validators_migrated", validators_migrated.to_string()))
+}
+
+// Moves validators stored in the legacy `State.validators` vector into the validator registry.
+// Insertion order follows the vector order, so the cron index carries over as is.
+fn migrate_validators_to_registry(storage: &mut dyn Storage) -> StdResult<u64> {
+    let legacy_validators = LEGACY_STATE.load(storage)?.validators;
+    if legacy_validators.is_empty() {
+        return Ok(0);
+    }
+
+    let mut state = STATE.load(storage)?;
+    // legacy validators were all staked with the config amount
+    let staked_amount = CONFIG.load(storage)?.amount_to_stake_per_validator;
+    for legacy_validator in legacy_validators {
+        let validator = ValidatorAccounts {
+            operator_address: legacy_validator.operator_address,
+            account_address: legacy_validator.account_address,
+            insertion_order: state.next_validator_insertion_order,
+            staked_amount,
+        };
+        validators().save(storage, &validator.operator_address, &validator)?;
+        state.next_validator_insertion_order += 1;
+        state.total_validators += 1;
+    }
+    // re-saving drops the legacy validators vector from the stored state
+    STATE.save(storage, &state)?;
+
+    Ok(state.total_validators)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -104,20 +160,50 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             timestamp2,
             from,
             to,
+            resolution,
         } => to_binary(&query_validators_aprs_by_interval(
-            deps, timestamp1, timestamp2, from, to,
+            deps,
+            timestamp1,
+            timestamp2,
+            from,
+            to,
+            resolution.unwrap_or(TimestampResolution::Exact),
         )?),
         QueryMsg::GetAprByValidator {
             timestamp1,
             timestamp2,
             addr,
-        } => to_binary(&query_validator_apr(deps, timestamp1, timestamp2, addr)?),
+            resolution,
+        } => to_binary(&query_validator_apr(
+            deps,
+            timestamp1,
+            timestamp2,
+            addr,
+            resolution.unwrap_or(TimestampResolution::Exact),
+        )?),
+        QueryMsg::GetAprWindows { addr, windows } => {
+            to_binary(&query_apr_windows(deps, addr, windows)?)
+        }
+        QueryMsg::GetSlashingAdjustedAprByValidator {
+            timestamp1,
+            timestamp2,
+            addr,
+        } => to_binary(&query_slashing_adjusted_apr(
+            deps, timestamp1, timestamp2, addr,
+        )?),
         QueryMsg::GetAllValidatorMetrics { addr } => {
             to_binary(&query_all_validator_metrics(deps, addr)?)
         }
-        QueryMsg::GetValidatorMetricsByTimestamp { addr, timestamp } => to_binary(
-            &query_validator_metrics_by_timestamp(deps, addr, timestamp)?,
-        ),
+        QueryMsg::GetValidatorMetricsByTimestamp {
+            addr,
+            timestamp,
+            resolution,
+        } => to_binary(&query_validator_metrics_by_timestamp(
+            deps,
+            addr,
+            timestamp,
+            resolution.unwrap_or(TimestampResolution::Exact),
+        )?),
         QueryMsg::GetValidatorsMetricsByTimestamp {
             timestamp,
             from,
@@ -125,6 +211,20 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         } => to_binary(&query_validators_metrics_by_timestamp(
             deps, timestamp, from, to,
         )?),
+        QueryMsg::GetValidatorMetricsByHeight {
+            addr,
+            from_height,
+            to_height,
+            start_after,
+            limit,
+        } => to_binary(&query_validator_metrics_by_height(
+            deps,
+            addr,
+            from_height,
+            to_height,
+            start_after,
+            limit,
+        )?),
         QueryMsg::GetValidatorMetricsBtwTimestamps {
             addr,
             timestamp1,
@@ -146,6 +246,59 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_binary(&get_off_chain_timestamp_meta_data(deps, timestamp)?)
         }
         QueryMsg::GetOffChainValidators {} => to_binary(&get_off_chain_validators(deps)?),
+        QueryMsg::GetValidators { start_after, limit } => {
+            to_binary(&query_validators(deps, start_after, limit)?)
+        }
+        QueryMsg::GetValidatorsByAccount { account_addr } => {
+            to_binary(&query_validators_by_account(deps, account_addr)?)
+        }
+        QueryMsg::GetRoles { addr } => to_binary(&query_roles(deps, addr)?),
+        QueryMsg::GetSlashingEvents {
+            validator,
+            start_after,
+            limit,
+        } => to_binary(&query_slashing_events(deps, validator, start_after, limit)?),
+        QueryMsg::GetCommissionChanges {
+            validator,
+            since,
+            limit,
+        } => to_binary(&query_commission_changes(deps, validator, since, limit)?),
+        QueryMsg::GetValidatorStatusHistory {
+            addr,
+            start_after,
+            limit,
+        } => to_binary(&query_validator_status_history(
+            deps,
+            addr,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::GetVotingPowerHistory { addr, from, to } => {
+            to_binary(&query_voting_power_history(deps, addr, from, to)?)
+        }
+        QueryMsg::GetWithdrawalRecipients {} => to_binary(&query_withdrawal_recipients(deps)?),
+        QueryMsg::GetPendingUnbondings { start_after, limit } => {
+            to_binary(&query_pending_unbondings(deps, start_after, limit)?)
+        }
+        QueryMsg::GetTimestampAggregate { timestamp } => {
+            to_binary(&query_timestamp_aggregate(deps, timestamp)?)
+        }
+        QueryMsg::GetCronStatus { timestamp } => to_binary(&query_cron_status(deps, timestamp)?),
+        QueryMsg::GetIncompleteCrons { start_after, limit } => {
+            to_binary(&query_incomplete_crons(deps, start_after, limit)?)
+        }
+        QueryMsg::GetValidatorScores {
+            timestamp1,
+            timestamp2,
+            start_after,
+            limit,
+        } => to_binary(&query_validator_scores(
+            deps,
+            timestamp1,
+            timestamp2,
+            start_after,
+            limit,
+        )?),
     }
 }
 
@@ -158,17 +311,62 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::RecordMetrics { timestamp } => {
+            let timestamp = resolve_cron_timestamp(deps.storage, &env, timestamp)?;
             record_validator_metrics(deps, env, info, timestamp)
         }
+        ExecuteMsg::ResumeCron { timestamp } => resume_cron(deps, env, info, timestamp),
+        ExecuteMsg::RecordVotingPower {
+            timestamp,
+            total_bonded,
+            voting_powers,
+        } => record_voting_power(deps, info, timestamp, total_bonded, voting_powers),
         ExecuteMsg::AddValidator {
             validator_opr_addr,
             account_addr,
         } => add_validator(deps, info, validator_opr_addr, account_addr),
-        ExecuteMsg::UpdateConfig { batch_size } => update_config(deps, info, batch_size),
+        ExecuteMsg::UpdateConfig {
+            batch_size,
+            commission_change_threshold,
+            refuse_new_cron_when_incomplete,
+            unbonding_period,
+        } => update_config(
+            deps,
+            info,
+            batch_size,
+            commission_change_threshold,
+            refuse_new_cron_when_incomplete,
+            unbonding_period,
+        ),
         ExecuteMsg::RemoveValidator {
             validator_oper_addr,
-        } => remove_validator(deps, info, validator_oper_addr),
-        ExecuteMsg::WithdrawFunds { amount } => withdraw_funds(deps, info, amount),
+        } => remove_validator(deps, env, info, validator_oper_addr),
+        ExecuteMsg::TopUpStake { validator } => top_up_stake(deps, env, info, validator),
+        ExecuteMsg::ReduceStake { validator, amount } => {
+            reduce_stake(deps, env, info, validator, amount)
+        }
+        ExecuteMsg::ReplaceValidator {
+            old,
+            new,
+            account_addr,
+        } => replace_validator(deps, env, info, old, new, account_addr),
+        ExecuteMsg::WithdrawFunds {
+            amount,
+            denom,
+            recipient,
+        } => withdraw_funds(deps, env, info, amount, denom, recipient),
+        ExecuteMsg::AllowWithdrawalRecipient { addr } => {
+            allow_withdrawal_recipient(deps, info, addr)
+        }
+        ExecuteMsg::DisallowWithdrawalRecipient { addr } => {
+            disallow_withdrawal_recipient(deps, info, addr)
+        }
+        ExecuteMsg::SweepMaturedUnbondings { limit } => {
+            sweep_matured_unbondings(deps, env, info, limit)
+        }
+        ExecuteMsg::ClaimRewards {
+            validators,
+            restake,
+        } => claim_rewards(deps, env, info, validators, restake.unwrap_or(false)),
 
         ExecuteMsg::DeleteMetricsForTimestamp {
             timestamp,
@@ -218,6 +416,24 @@ pub fn execute(
             timestamp,
             validator_metrics,
         } => add_off_chain_validator_metrics(deps, info, timestamp, validator_metrics),
+
+        ExecuteMsg::GrantRole { role, addr } => grant_role(deps, info, role, addr),
+        ExecuteMsg::RevokeRole { role, addr } => revoke_role(deps, info, role, addr),
+        ExecuteMsg::ProposeNewManager {
+            new_manager,
+            expiry_in_seconds,
+        } => propose_new_manager(deps, env, info, new_manager, expiry_in_seconds),
+        ExecuteMsg::AcceptManagership {} => accept_managership(deps, env, info),
+        ExecuteMsg::CancelManagerProposal {} => cancel_manager_proposal(deps, info),
+        ExecuteMsg::UpdateScoreWeights { score_weights } => {
+            update_score_weights(deps, info, score_weights)
+        }
+        ExecuteMsg::UpdateCronSchedule { cron_schedule } => {
+            update_cron_schedule(deps, info, cron_schedule)
+        }
+        ExecuteMsg::UpdateKeeperIncentive { keeper_incentive } => {
+            update_keeper_incentive(deps, info, keeper_incentive)
+        }
     }
 }
 //
@@ -232,7 +448,7 @@ fn remove_timestamp(
     timestamp: u64,
 ) -> Result<Response, ContractError> {
     // can only be called by manager
-    if !sender_is_manager(&deps, &info) {
+    if !sender_has_role(deps.storage, &info.sender, Role::Pruner)? {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -250,6 +466,8 @@ fn remove_timestamp(
     if new_timestamps.len() < existing_timestamps_length {
         state.cron_timestamps = new_timestamps;
         STATE.save(deps.storage, &state)?;
+        TIMESTAMP_AGGREGATES.remove(deps.storage, U64Key::new(timestamp));
+        CRON_RECORDS.remove(deps.storage, U64Key::new(timestamp));
         timestamp_existed = true;
     }
 
@@ -266,9 +484,143 @@ fn remove_timestamp(
         ))
 }
 
-fn sender_is_manager(deps: &DepsMut, info: &MessageInfo) -> bool {
-    let config = CONFIG.load(deps.storage).unwrap();
-    info.sender == config.manager
+// The manager and admins implicitly hold every role
+fn sender_has_role(storage: &dyn Storage, sender: &Addr, role: Role) -> StdResult<bool> {
+    let config = CONFIG.load(storage)?;
+    if sender.eq(&config.manager) || ROLES.has(storage, (Role::Admin.as_str(), sender)) {
+        return Ok(true);
+    }
+    Ok(ROLES.has(storage, (role.as_str(), sender)))
+}
+
+// Only the current manager can hand over the contract, the new manager has to accept it.
+fn propose_new_manager(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    new_manager: String,
+    expiry_in_seconds: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.manager {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let new_manager = deps.api.addr_validate(&new_manager)?;
+    // an expiry past the end of time never expires
+    let expires_at =
+        expiry_in_seconds.map(|expiry| env.block.time.seconds().saturating_add(expiry));
+
+    CONFIG.update(deps.storage, |mut conf| -> StdResult<_> {
+        conf.pending_manager = Some(PendingManager {
+            addr: new_manager.clone(),
+            expires_at,
+        });
+        Ok(conf)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "propose_new_manager")
+        .add_attribute("pending_manager", new_manager)
+        .add_attribute(
+            "expires_at",
+            expires_at.map_or("never".to_string(), |t| t.to_string()),
+        ))
+}
+
+fn accept_managership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let pending_manager = match config.pending_manager {
+        Some(pending_manager) => pending_manager,
+        None => return Err(ContractError::NoPendingManager {}),
+    };
+
+    if info.sender != pending_manager.addr {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(expires_at) = pending_manager.expires_at {
+        if env.block.time.seconds() > expires_at {
+            return Err(ContractError::ManagerProposalExpired {});
+        }
+    }
+
+    CONFIG.update(deps.storage, |mut conf| -> StdResult<_> {
+        conf.manager = pending_manager.addr.clone();
+        conf.pending_manager = None;
+        Ok(conf)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "accept_managership")
+        .add_attribute("previous_manager", config.manager)
+        .add_attribute("new_manager", pending_manager.addr))
+}
+
+fn cancel_manager_proposal(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.manager {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if config.pending_manager.is_none() {
+        return Err(ContractError::NoPendingManager {});
+    }
+
+    CONFIG.update(deps.storage, |mut conf| -> StdResult<_> {
+        conf.pending_manager = None;
+        Ok(conf)
+    })?;
+
+    Ok(Response::new().add_attribute("method", "cancel_manager_proposal"))
+}
+
+fn grant_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    addr: String,
+) -> Result<Response, ContractError> {
+    if !sender_has_role(deps.storage, &info.sender, Role::Admin)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let addr = deps.api.addr_validate(&addr)?;
+    if ROLES.has(deps.storage, (role.as_str(), &addr)) {
+        return Err(ContractError::RoleAlreadyGranted {});
+    }
+    ROLES.save(deps.storage, (role.as_str(), &addr), &true)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "grant_role")
+        .add_attribute("role", role.as_str())
+        .add_attribute("addr", addr))
+}
+
+fn revoke_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    addr: String,
+) -> Result<Response, ContractError> {
+    if !sender_has_role(deps.storage, &info.sender, Role::Admin)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let addr = deps.api.addr_validate(&addr)?;
+    if !ROLES.has(deps.storage, (role.as_str(), &addr)) {
+        return Err(ContractError::RoleNotGranted {});
+    }
+    ROLES.remove(deps.storage, (role.as_str(), &addr));
+
+    Ok(Response::new()
+        .add_attribute("method", "revoke_role")
+        .add_attribute("role", role.as_str())
+        .add_attribute("addr", addr))
 }
 
 fn delete_metrics_for_timestamp(
@@ -278,32 +630,38 @@ fn delete_metrics_for_timestamp(
     validator_start: usize,
     validator_ct: usize,
 ) -> Result<Response, ContractError> {
-    if !sender_is_manager(&deps, &info) {
+    if !sender_has_role(deps.storage, &info.sender, Role::Pruner)? {
         return Err(ContractError::Unauthorized {});
     }
     //for every validator, in range, remove the metrics
-    let state = STATE.load(deps.storage)?;
-    if state.validators.len().le(&validator_start) {
+    let total_validators = STATE.load(deps.storage)?.total_validators as usize;
+    if total_validators.le(&validator_start) {
         return Err(ContractError::ValidatorOutOfRange {});
     }
 
-    let validator_end = min(validator_start + validator_ct, state.validators.len());
+    let validator_end = min(validator_start + validator_ct, total_validators);
 
-    state.validators[validator_start..validator_end]
-        .iter()
-        .for_each(|validator| {
-            METRICS_HISTORY.remove(
-                deps.storage,
-                (&validator.operator_address, U64Key::from(timestamp)),
-            );
-        });
+    get_validators_by_position(
+        deps.storage,
+        validator_start,
+        validator_end - validator_start,
+    )?
+    .iter()
+    .for_each(|validator| {
+        METRICS_HISTORY.remove(
+            deps.storage,
+            (&validator.operator_address, U64Key::from(timestamp)),
+        );
+    });
+    // the stats no longer match the remaining records
+    TIMESTAMP_AGGREGATES.remove(deps.storage, U64Key::new(timestamp));
 
     Ok(Response::new()
         .add_attribute("method", "delete_metrics_for_timestamp")
         .add_attribute("deleted_timestamp", timestamp.to_string())
         .add_attribute(
             "validators_left",
-            (state.validators.len() - validator_end).to_string(),
+            (total_validators - validator_end).to_string(),
         )
         .add_attribute("next_validator_idx", validator_end.to_string()))
 }
@@ -317,6 +675,7 @@ fn query_validator_apr(
     timestamp1: u64,
     timestamp2: u64,
     addr: Addr,
+    resolution: TimestampResolution,
 ) -> StdResult<ValidatorAprResponse> {
     if timestamp1.ge(&timestamp2) {
         return Err(StdError::GenericErr {
@@ -324,13 +683,277 @@ fn query_validator_apr(
         });
     }
 
+    let (h1, h2) =
+        resolve_metrics_interval(deps.storage, &addr, timestamp1, timestamp2, &resolution)?
+            .ok_or_else(|| StdError::not_found("ValidatorMetrics"))?;
+
+    get_validator_apr_response(deps, &h1, &h2)
+}
+
+fn query_apr_windows(deps: Deps, addr: Addr, windows: Vec<u64>) -> StdResult<AprWindowsResponse> {
+    let latest_cron = *STATE
+        .load(deps.storage)?
+        .cron_timestamps
+        .last()
+        .ok_or_else(|| StdError::not_found("cron timestamp"))?;
+    let resolve = |timestamp: u64| -> StdResult<ValidatorMetrics> {
+        resolve_metrics(
+            deps.storage,
+            &addr,
+            timestamp,
+            &TimestampResolution::Nearest,
+        )?
+        .ok_or_else(|| StdError::not_found("ValidatorMetrics"))
+    };
+
+    // a validator that missed the latest crons ends at its own latest record, see timestamp2
+    let h2 = resolve(latest_cron)?;
+    let mut apr_windows: Vec<AprWindow> = vec![];
+    for window in windows {
+        // the first record is the nearest one to timestamp 0
+        let target = if window == 0 {
+            0
+        } else {
+            latest_cron.saturating_sub(window)
+        };
+        let h1 = resolve(target)?;
+
+        // apr can't be computed without an interval or against a zero delegation
+        let apr = if h1.timestamp < h2.timestamp && !h1.delegated_amount.is_zero() {
+            Some(get_validator_apr_response(deps, &h1, &h2)?)
+        } else {
+            None
+        };
+
+        apr_windows.push(AprWindow {
+            window,
+            timestamp1: h1.timestamp,
+            timestamp2: h2.timestamp,
+            apr,
+        });
+    }
+
+    Ok(AprWindowsResponse {
+        addr,
+        latest_timestamp: latest_cron,
+        windows: apr_windows,
+    })
+}
+
+// Recorded metrics of the validator for the timestamp, resolved with the given mode.
+// Nearest picks the earlier record on a tie.
+fn resolve_metrics(
+    storage: &dyn Storage,
+    addr: &Addr,
+    timestamp: u64,
+    resolution: &TimestampResolution,
+) -> StdResult<Option<ValidatorMetrics>> {
+    if let TimestampResolution::Exact = resolution {
+        return METRICS_HISTORY.may_load(storage, (addr, U64Key::new(timestamp)));
+    }
+
+    let floor = || -> StdResult<Option<ValidatorMetrics>> {
+        Ok(METRICS_HISTORY
+            .prefix(addr)
+            .range(
+                storage,
+                None,
+                Some(Bound::Inclusive(U64Key::new(timestamp).into())),
+                Order::Descending,
+            )
+            .next()
+            .transpose()?
+            .map(|(_, metric)| metric))
+    };
+    let ceil = || -> StdResult<Option<ValidatorMetrics>> {
+        Ok(METRICS_HISTORY
+            .prefix(addr)
+            .range(
+                storage,
+                Some(Bound::Inclusive(U64Key::new(timestamp).into())),
+                None,
+                Order::Ascending,
+            )
+            .next()
+            .transpose()?
+            .map(|(_, metric)| metric))
+    };
+
+    Ok(match resolution {
+        TimestampResolution::Exact => unreachable!(),
+        TimestampResolution::Floor => floor()?,
+        TimestampResolution::Ceil => ceil()?,
+        TimestampResolution::Nearest => match (floor()?, ceil()?) {
+            (Some(floor), Some(ceil)) => {
+                if timestamp - floor.timestamp <= ceil.timestamp - timestamp {
+                    Some(floor)
+                } else {
+                    Some(ceil)
+                }
+            }
+            (floor, ceil) => floor.or(ceil),
+        },
+    })
+}
+
+// Resolves both ends of an apr interval, None if either is missing or they resolve to the
+// same (or reversed) records.
+fn resolve_metrics_interval(
+    storage: &dyn Storage,
+    addr: &Addr,
+    timestamp1: u64,
+    timestamp2: u64,
+    resolution: &TimestampResolution,
+) -> StdResult<Option<(ValidatorMetrics, ValidatorMetrics)>> {
+    let h1_opt = resolve_metrics(storage, addr, timestamp1, resolution)?;
+    let h2_opt = resolve_metrics(storage, addr, timestamp2, resolution)?;
+    match (h1_opt, h2_opt) {
+        (Some(h1), Some(h2)) if h1.timestamp < h2.timestamp => Ok(Some((h1, h2))),
+        _ => Ok(None),
+    }
+}
+
+fn query_slashing_adjusted_apr(
+    deps: Deps,
+    timestamp1: u64,
+    timestamp2: u64,
+    addr: Addr,
+) -> StdResult<SlashingAdjustedAprResponse> {
+    if timestamp1.ge(&timestamp2) {
+        return Err(StdError::GenericErr {
+            msg: "timestamp1 cannot be greater than or equal to timestamp2".to_string(),
+        });
+    }
+
     let h1 = METRICS_HISTORY.load(deps.storage, (&addr, U64Key::new(timestamp1)))?;
 
     let h2 = METRICS_HISTORY.load(deps.storage, (&addr, U64Key::new(timestamp2)))?;
 
-    Ok(ValidatorAprResponse {
+    let slashing_ratio = if h1.slashing_pointer.is_zero() {
+        Decimal::one()
+    } else {
+        decimal_division_in_256(h2.slashing_pointer, h1.slashing_pointer)
+    };
+
+    Ok(SlashingAdjustedAprResponse {
         addr,
         apr: compute_apr(&h1, &h2, timestamp2 - timestamp1)?,
+        slashing_adjusted_apr: compute_slashing_adjusted_apr(&h1, &h2, timestamp2 - timestamp1)?,
+        slashing_ratio,
+        principal_lost: compute_principal_lost_to_slashing(&h1, &h2),
+    })
+}
+
+// Scores every validator with metrics at both timestamps, sorted from best to worst.
+// All validators have to be scored before paginating, as scores are relative to each other.
+fn query_validator_scores(
+    deps: Deps,
+    timestamp1: u64,
+    timestamp2: u64,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ValidatorScore>> {
+    if timestamp1.ge(&timestamp2) {
+        return Err(StdError::GenericErr {
+            msg: "timestamp1 cannot be greater than or equal to timestamp2".to_string(),
+        });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let crons_in_interval = STATE
+        .load(deps.storage)?
+        .cron_timestamps
+        .into_iter()
+        .filter(|t| *t >= timestamp1 && *t <= timestamp2)
+        .count() as u64;
+
+    let mut inputs: Vec<ScoreInputs> = vec![];
+    for item in validators().range(deps.storage, None, None, Order::Ascending) {
+        let (_, validator) = item?;
+        let interval = resolve_metrics_interval(
+            deps.storage,
+            &validator.operator_address,
+            timestamp1,
+            timestamp2,
+            &TimestampResolution::Exact,
+        )?;
+        let (h1, h2) = match interval {
+            Some(interval) => interval,
+            None => continue,
+        };
+
+        let recorded = query_all_validator_metrics_btw_timestamps(
+            deps,
+            h1.operator_addr.clone(),
+            timestamp1,
+            timestamp2,
+        )?
+        .len() as u64;
+
+        inputs.push(ScoreInputs {
+            addr: validator.operator_address,
+            apr: compute_apr(&h1, &h2, timestamp2 - timestamp1)?,
+            commission: h2.commission,
+            max_commission: h2.max_commission,
+            self_delegation_ratio: if h2.delegated_amount.is_zero() {
+                Decimal::zero()
+            } else {
+                Decimal::from_ratio(h2.self_delegated_amount, h2.delegated_amount)
+            },
+            slashing_ratio: if h1.slashing_pointer.is_zero() {
+                Decimal::one()
+            } else {
+                decimal_division_in_256(h2.slashing_pointer, h1.slashing_pointer)
+            },
+            recorded_ratio: if crons_in_interval == 0 {
+                Decimal::one()
+            } else {
+                Decimal::from_ratio(recorded, crons_in_interval)
+            },
+        });
+    }
+
+    let scores = compute_scores(inputs, &config.score_weights);
+    let start = match start_after {
+        Some(addr) => scores
+            .iter()
+            .position(|score| score.addr.eq(&addr))
+            .map_or(scores.len(), |position| position + 1),
+        None => 0,
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    Ok(scores.into_iter().skip(start).take(limit).collect())
+}
+
+fn get_validator_apr_response(
+    deps: Deps,
+    h1: &ValidatorMetrics,
+    h2: &ValidatorMetrics,
+) -> StdResult<ValidatorAprResponse> {
+    let apr = compute_apr(h1, h2, h2.timestamp - h1.timestamp)?;
+
+    let metrics_in_interval: Vec<ValidatorMetrics> = query_all_validator_metrics_btw_timestamps(
+        deps,
+        h1.operator_addr.clone(),
+        h1.timestamp,
+        h2.timestamp,
+    )?
+    .into_iter()
+    .map(|(_, metric)| metric)
+    .collect();
+    let avg_commission = compute_time_weighted_commission(&metrics_in_interval)?;
+
+    Ok(ValidatorAprResponse {
+        addr: h2.operator_addr.clone(),
+        timestamp1: h1.timestamp,
+        timestamp2: h2.timestamp,
+        apr,
+        gross_apr: compute_gross_apr(apr, avg_commission),
+        slashing_adjusted_apr: compute_slashing_adjusted_apr(h1, h2, h2.timestamp - h1.timestamp)?,
+        avg_commission,
+        start_commission: h1.commission,
+        end_commission: h2.commission,
     })
 }
 
@@ -340,15 +963,14 @@ fn query_validators_aprs_by_interval(
     timestamp2: u64,
     from: u64,
     to: u64,
+    resolution: TimestampResolution,
 ) -> StdResult<Vec<ValidatorAprResponse>> {
     if timestamp1.ge(&timestamp2) {
         return Err(StdError::GenericErr {
             msg: "timestamp1 cannot be greater than or equal to timestamp2".to_string(),
         });
     }
-    let validators = STATE.load(deps.storage)?.validators;
-
-    let total_validators: u64 = validators.len() as u64;
+    let total_validators: u64 = STATE.load(deps.storage)?.total_validators;
 
     if to.ge(&total_validators) || from > to {
         return Err(StdError::GenericErr {
@@ -356,26 +978,21 @@ fn query_validators_aprs_by_interval(
         });
     }
 
-    let t1 = U64Key::new(timestamp1);
-    let t2 = U64Key::new(timestamp2);
+    let validators =
+        get_validators_by_position(deps.storage, from as usize, (to - from + 1) as usize)?;
 
     let mut response: Vec<ValidatorAprResponse> = vec![];
-    let mut start = from;
-
-    while start.le(&to) {
-        let validator_addr = &validators[start as usize];
-        let h1_opt =
-            METRICS_HISTORY.may_load(deps.storage, (&validator_addr.operator_address, t1.clone()));
-        let h2_opt =
-            METRICS_HISTORY.may_load(deps.storage, (&validator_addr.operator_address, t2.clone()));
-        if let (Ok(Some(h1)), Ok(Some(h2))) = (h1_opt, h2_opt) {
-            let apr = compute_apr(&h1, &h2, timestamp2 - timestamp1)?;
-            response.push(ValidatorAprResponse {
-                addr: h2.operator_addr,
-                apr,
-            });
+
+    for validator_addr in validators.iter() {
+        if let Some((h1, h2)) = resolve_metrics_interval(
+            deps.storage,
+            &validator_addr.operator_address,
+            timestamp1,
+            timestamp2,
+            &resolution,
+        )? {
+            response.push(get_validator_apr_response(deps, &h1, &h2)?);
         };
-        start += 1;
     }
 
     Ok(response)
@@ -384,27 +1001,111 @@ fn query_validators_aprs_by_interval(
 fn update_config(
     deps: DepsMut,
     info: MessageInfo,
-    batch_size: u64,
+    batch_size: Option<u64>,
+    commission_change_threshold: Option<Decimal>,
+    refuse_new_cron_when_incomplete: Option<bool>,
+    unbonding_period: Option<u64>,
 ) -> Result<Response, ContractError> {
-    if batch_size == 0 {
+    if batch_size == Some(0) {
         return Err(ContractError::BatchSizeCannotBeZero {});
     }
 
-    if !sender_is_manager(&deps, &info) {
+    if !sender_has_role(deps.storage, &info.sender, Role::Admin)? {
         return Err(ContractError::Unauthorized {});
     }
 
-    CONFIG.update(deps.storage, |mut conf| -> StdResult<_> {
-        conf.batch_size = batch_size;
+    let config = CONFIG.update(deps.storage, |mut conf| -> StdResult<_> {
+        if let Some(batch_size) = batch_size {
+            conf.batch_size = batch_size;
+        }
+        if let Some(commission_change_threshold) = commission_change_threshold {
+            conf.commission_change_threshold = commission_change_threshold;
+        }
+        if let Some(refuse_new_cron_when_incomplete) = refuse_new_cron_when_incomplete {
+            conf.refuse_new_cron_when_incomplete = refuse_new_cron_when_incomplete;
+        }
+        if let Some(unbonding_period) = unbonding_period {
+            conf.unbonding_period = unbonding_period;
+        }
         Ok(conf)
     })?;
 
     Ok(Response::new()
         .add_attribute("method", "update_config")
-        .add_attribute("new_batch_size", batch_size.to_string()))
+        .add_attribute("new_batch_size", config.batch_size.to_string())
+        .add_attribute(
+            "new_commission_change_threshold",
+            config.commission_change_threshold.to_string(),
+        )
+        .add_attribute(
+            "refuse_new_cron_when_incomplete",
+            config.refuse_new_cron_when_incomplete.to_string(),
+        )
+        .add_attribute("new_unbonding_period", config.unbonding_period.to_string()))
 }
 
-fn add_validator(
+fn update_cron_schedule(
+    deps: DepsMut,
+    info: MessageInfo,
+    cron_schedule: Option<CronSchedule>,
+) -> Result<Response, ContractError> {
+    if !sender_has_role(deps.storage, &info.sender, Role::Admin)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if matches!(&cron_schedule, Some(s) if s.interval == 0) {
+        return Err(ContractError::InvalidCronSchedule {});
+    }
+
+    CONFIG.update(deps.storage, |mut conf| -> StdResult<_> {
+        conf.cron_schedule = cron_schedule;
+        Ok(conf)
+    })?;
+
+    Ok(Response::new().add_attribute("method", "update_cron_schedule"))
+}
+
+fn update_keeper_incentive(
+    deps: DepsMut,
+    info: MessageInfo,
+    keeper_incentive: Option<KeeperIncentive>,
+) -> Result<Response, ContractError> {
+    if !sender_has_role(deps.storage, &info.sender, Role::Admin)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
+    if keeper_incentive.is_some() && config.cron_schedule.is_none() {
+        return Err(ContractError::KeeperModeRequiresCronSchedule {});
+    }
+    config.keeper_incentive = keeper_incentive;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("method", "update_keeper_incentive"))
+}
+
+fn update_score_weights(
+    deps: DepsMut,
+    info: MessageInfo,
+    score_weights: ScoreWeights,
+) -> Result<Response, ContractError> {
+    if !sender_has_role(deps.storage, &info.sender, Role::Admin)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if total_weight(&score_weights).is_zero() {
+        return Err(ContractError::InvalidScoreWeights {});
+    }
+
+    CONFIG.update(deps.storage, |mut conf| -> StdResult<_> {
+        conf.score_weights = score_weights;
+        Ok(conf)
+    })?;
+
+    Ok(Response::new().add_attribute("method", "update_score_weights"))
+}
+
+fn add_validator(
     deps: DepsMut,
     info: MessageInfo,
     validator_addr: Addr,
@@ -417,7 +1118,7 @@ fn add_validator(
     let amount_to_stake_per_validator = config.amount_to_stake_per_validator;
 
     // can only be called by manager
-    if info.sender != config.manager {
+    if !sender_has_role(deps.storage, &info.sender, Role::Admin)? {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -431,92 +1132,559 @@ fn add_validator(
     }
 
     // Validator should not be already recorded
-    if state
-        .validators
-        .iter()
-        .any(|addr| addr.operator_address.eq(&validator_addr))
+    if validators()
+        .may_load(deps.storage, &validator_addr)?
+        .is_some()
     {
         return Err(ContractError::ValidatorAlreadyExists {});
     }
 
-    let funds = info.funds.first();
-    if funds.is_none() {
-        return Err(ContractError::NoFundsFound {});
-    }
-
-    if funds.unwrap().amount.lt(&amount_to_stake_per_validator) {
+    let funds = get_vault_denom_funds(&info, &vault_denom)?;
+    if funds.lt(&amount_to_stake_per_validator) {
         return Err(ContractError::InsufficientFunds {});
     }
 
-    let msg = StakingMsg::Delegate {
+    let mut msgs: Vec<CosmosMsg> = vec![StakingMsg::Delegate {
         validator: validator_addr.to_string(),
         amount: Coin {
-            denom: vault_denom,
+            denom: vault_denom.clone(),
             amount: amount_to_stake_per_validator,
         },
-    };
+    }
+    .into()];
+
+    // anything above the stake is sent back instead of staying in the contract
+    let surplus = funds - amount_to_stake_per_validator;
+    if !surplus.is_zero() {
+        msgs.push(
+            BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![Coin {
+                    denom: vault_denom,
+                    amount: surplus,
+                }],
+            }
+            .into(),
+        );
+    }
 
     // since deps is borrowed as mutable below, borrowing it immutably here
     let validator_account_addr = deps.api.addr_validate(&wallet_addrress).unwrap();
 
+    let current_validator = ValidatorAccounts {
+        operator_address: validator_addr.clone(),
+        account_address: validator_account_addr,
+        insertion_order: state.next_validator_insertion_order,
+        staked_amount: amount_to_stake_per_validator,
+    };
+    validators().save(deps.storage, &validator_addr, &current_validator)?;
+
     STATE.update(deps.storage, |mut s: State| -> StdResult<_> {
-        let current_validator = ValidatorAccounts {
-            operator_address: validator_addr.clone(),
-            account_address: validator_account_addr,
-        };
-        s.validators.push(current_validator);
+        s.next_validator_insertion_order += 1;
+        s.total_validators += 1;
         Ok(s)
     })?;
 
     Ok(Response::new()
-        .add_messages(vec![msg])
+        .add_messages(msgs)
         .add_attribute("method", "add_validator"))
 }
 
+// Funds must be a single coin of the vault denom
+fn get_vault_denom_funds(info: &MessageInfo, vault_denom: &str) -> Result<Uint128, ContractError> {
+    let funds = match info.funds.as_slice() {
+        [] => return Err(ContractError::NoFundsFound {}),
+        [funds] => funds,
+        _ => return Err(ContractError::MultipleCoinsSent {}),
+    };
+
+    if funds.denom != vault_denom {
+        return Err(ContractError::InvalidFundsDenom {
+            expected: vault_denom.to_string(),
+            found: funds.denom.clone(),
+        });
+    }
+    if funds.amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    Ok(funds.amount)
+}
+
+fn top_up_stake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    validator_addr: Addr,
+) -> Result<Response, ContractError> {
+    if !sender_has_role(deps.storage, &info.sender, Role::Admin)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut validator = validators()
+        .may_load(deps.storage, &validator_addr)?
+        .ok_or(ContractError::ValidatorDoesNotExist {})?;
+    let vault_denom = STATE.load(deps.storage)?.vault_denom;
+    let amount = get_vault_denom_funds(&info, &vault_denom)?;
+
+    let accumulated_rewards = deps
+        .querier
+        .query_delegation(&env.contract.address, &validator_addr)?
+        .map(|delegation| delegation.accumulated_rewards)
+        .unwrap_or_default();
+    let mut baseline =
+        rebase_rewards_baseline(deps.storage, &validator_addr, &accumulated_rewards)?;
+    baseline.delegated += amount;
+    REWARDS_BASELINES.save(deps.storage, &validator_addr, &baseline)?;
+
+    validator.staked_amount += amount;
+    validators().save(deps.storage, &validator_addr, &validator)?;
+
+    Ok(Response::new()
+        .add_message(StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: Coin::new(amount.u128(), vault_denom),
+        })
+        .add_attribute("method", "top_up_stake")
+        .add_attribute("validator", validator_addr)
+        .add_attribute("staked_amount", validator.staked_amount))
+}
+
+fn reduce_stake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    validator_addr: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if !sender_has_role(deps.storage, &info.sender, Role::Admin)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut validator = validators()
+        .may_load(deps.storage, &validator_addr)?
+        .ok_or(ContractError::ValidatorDoesNotExist {})?;
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    // the delegation can be below the staked amount after a slashing
+    let delegation = deps
+        .querier
+        .query_delegation(&env.contract.address, &validator_addr)?
+        .ok_or_else(|| ContractError::NoDelegationFound {
+            manager: env.contract.address.clone(),
+            validator: validator_addr.clone(),
+        })?;
+    if amount > delegation.amount.amount {
+        return Err(ContractError::InsufficientFunds {});
+    }
+    // a validator without delegation can't be recorded, it has to be removed instead
+    if amount == delegation.amount.amount {
+        return Err(ContractError::CannotReduceStakeToZero {});
+    }
+
+    let mut baseline = rebase_rewards_baseline(
+        deps.storage,
+        &validator_addr,
+        &delegation.accumulated_rewards,
+    )?;
+    baseline.undelegated += amount;
+    REWARDS_BASELINES.save(deps.storage, &validator_addr, &baseline)?;
+
+    let mut state = STATE.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+    let amount = Coin::new(amount.u128(), state.vault_denom.clone());
+    let unbonding = save_unbonding(
+        deps.storage,
+        &mut state,
+        &config,
+        &env,
+        &validator_addr,
+        amount.clone(),
+    )?;
+    STATE.save(deps.storage, &state)?;
+
+    validator.staked_amount = validator.staked_amount.saturating_sub(amount.amount);
+    validators().save(deps.storage, &validator_addr, &validator)?;
+
+    Ok(Response::new()
+        .add_message(StakingMsg::Undelegate {
+            validator: validator_addr.to_string(),
+            amount,
+        })
+        .add_attribute("method", "reduce_stake")
+        .add_attribute("validator", validator_addr)
+        .add_attribute("staked_amount", validator.staked_amount)
+        .add_attribute("unbonding_id", unbonding.id.to_string())
+        .add_attribute("completes_at", unbonding.completes_at.to_string()))
+}
+
+// Adds an undelegation to the unbonding ledger, `state` is saved by the caller
+fn save_unbonding(
+    storage: &mut dyn Storage,
+    state: &mut State,
+    config: &Config,
+    env: &Env,
+    validator: &Addr,
+    amount: Coin,
+) -> StdResult<UnbondingEntry> {
+    let started_at = env.block.time.seconds();
+    let unbonding = UnbondingEntry {
+        id: state.next_unbonding_id,
+        validator: validator.clone(),
+        amount,
+        started_at,
+        completes_at: started_at.saturating_add(config.unbonding_period),
+    };
+    UNBONDINGS.save(storage, U64Key::new(unbonding.id), &unbonding)?;
+    state.next_unbonding_id += 1;
+    state.total_unbonding += unbonding.amount.amount;
+
+    Ok(unbonding)
+}
+
 fn remove_validator(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     val_opr_addr: Addr,
 ) -> Result<Response, ContractError> {
     let mut state = STATE.load(deps.storage)?;
     let config = CONFIG.load(deps.storage)?;
 
-    let vault_denom = state.vault_denom.clone();
-    let amount_to_stake_per_validator = config.amount_to_stake_per_validator;
-
     // can only be called by manager
-    if info.sender != config.manager {
+    if !sender_has_role(deps.storage, &info.sender, Role::Admin)? {
         return Err(ContractError::Unauthorized {});
     }
 
-    let val_len = state.validators.len();
+    if validators()
+        .may_load(deps.storage, &val_opr_addr)?
+        .is_none()
+    {
+        return Err(ContractError::ValidatorDoesNotExist {});
+    }
+
+    // the delegation can differ from the staked amount through restaked rewards or slashing
+    let delegation = deps
+        .querier
+        .query_delegation(&env.contract.address, &val_opr_addr)?
+        .map(|delegation| delegation.amount)
+        .filter(|amount| !amount.amount.is_zero());
 
-    /* note: this seems unoptimized, primarily due to equality op being required.
-       with a change to state can be optimized, i.e. if validators could be queried by validator address
-    */
-    let other_validators = state
-        .validators
-        .into_iter()
-        .filter(|addr| !addr.operator_address.eq(&val_opr_addr))
-        .collect::<Vec<ValidatorAccounts>>();
-    if other_validators.len().eq(&val_len) {
+    let mut response = Response::new().add_attribute("method", "remove_validator");
+    if let Some(amount) = delegation {
+        let unbonding = save_unbonding(
+            deps.storage,
+            &mut state,
+            &config,
+            &env,
+            &val_opr_addr,
+            amount.clone(),
+        )?;
+        response = response
+            .add_message(StakingMsg::Undelegate {
+                validator: val_opr_addr.to_string(),
+                amount,
+            })
+            .add_attribute("unbonding_id", unbonding.id.to_string())
+            .add_attribute("completes_at", unbonding.completes_at.to_string());
+    }
+
+    // a validator added again later starts without the claims of this delegation
+    validators().remove(deps.storage, &val_opr_addr)?;
+    REWARDS_BASELINES.remove(deps.storage, &val_opr_addr);
+    state.total_validators -= 1;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(response)
+}
+
+// Completed unbondings are paid out to the contract balance by the chain, this only drops them from
+// the ledger.
+fn sweep_matured_unbondings(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    if !sender_has_role(deps.storage, &info.sender, Role::Admin)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let now = env.block.time.seconds();
+    let matured: Vec<UnbondingEntry> = UNBONDINGS
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref()
+                .map_or(true, |(_, entry)| entry.completes_at <= now)
+        })
+        .take(limit)
+        .map(|item| item.map(|(_, entry)| entry))
+        .collect::<StdResult<_>>()?;
+
+    let mut swept_amount = Uint128::zero();
+    for entry in matured.iter() {
+        UNBONDINGS.remove(deps.storage, U64Key::new(entry.id));
+        swept_amount += entry.amount.amount;
+    }
+    STATE.update(deps.storage, |mut s| -> StdResult<_> {
+        s.total_unbonding = s.total_unbonding.saturating_sub(swept_amount);
+        Ok(s)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "sweep_matured_unbondings")
+        .add_attribute("unbondings_swept", matured.len().to_string())
+        .add_attribute("amount_swept", swept_amount))
+}
+
+// Moves our stake from `old` to `new` with a redelegation, so it isn't locked unbonding. The history
+// of `old` is kept, `new` is tracked from scratch with a new insertion order.
+fn replace_validator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    old: Addr,
+    new: Addr,
+    account_addr: String,
+) -> Result<Response, ContractError> {
+    if !sender_has_role(deps.storage, &info.sender, Role::Admin)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let old_validator = validators()
+        .may_load(deps.storage, &old)?
+        .ok_or(ContractError::ValidatorDoesNotExist {})?;
+    if deps.querier.query_validator(new.clone())?.is_none() {
         return Err(ContractError::ValidatorDoesNotExist {});
     }
+    if validators().may_load(deps.storage, &new)?.is_some() {
+        return Err(ContractError::ValidatorAlreadyExists {});
+    }
+    let account_address = deps.api.addr_validate(&account_addr)?;
 
-    let msg = StakingMsg::Undelegate {
-        validator: val_opr_addr.to_string(),
-        amount: Coin {
-            denom: vault_denom,
-            amount: amount_to_stake_per_validator,
-        },
+    let delegation = deps
+        .querier
+        .query_delegation(&env.contract.address, &old)?
+        .ok_or_else(|| ContractError::NoDelegationFound {
+            manager: env.contract.address.clone(),
+            validator: old.clone(),
+        })?;
+    // a delegation that was redelegated to `old` can't be moved again until that one completes
+    if delegation.can_redelegate.amount < delegation.amount.amount {
+        return Err(ContractError::RedelegationNotAvailable {});
+    }
+
+    let msg = StakingMsg::Redelegate {
+        src_validator: old.to_string(),
+        dst_validator: new.to_string(),
+        amount: delegation.amount.clone(),
     };
 
-    state.validators = other_validators;
+    // the redelegation withdraws the rewards of `old`, a record at the block time closes its history
+    // with them. Nothing is recorded when it is out of the active set.
+    let timestamp = env.block.time.seconds();
+    let (final_metrics, _) = compute_current_metrics(
+        &deps,
+        env.clone(),
+        std::slice::from_ref(&old_validator),
+        timestamp,
+    )?;
+    for metric in final_metrics {
+        let key = (&old, U64Key::new(timestamp));
+        if !METRICS_HISTORY.has(deps.storage, key.clone()) {
+            METRICS_HISTORY.save(deps.storage, key, &metric)?;
+        }
+    }
+    let withdrawn_rewards = delegation
+        .accumulated_rewards
+        .iter()
+        .map(|coin| coin.to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+
+    validators().remove(deps.storage, &old)?;
+    REWARDS_BASELINES.remove(deps.storage, &old);
+
+    let mut state = STATE.load(deps.storage)?;
+    let new_validator = ValidatorAccounts {
+        operator_address: new.clone(),
+        account_address,
+        insertion_order: state.next_validator_insertion_order,
+        staked_amount: old_validator.staked_amount,
+    };
+    validators().save(deps.storage, &new, &new_validator)?;
+    state.next_validator_insertion_order += 1;
     STATE.save(deps.storage, &state)?;
 
     Ok(Response::new()
-        .add_messages(vec![msg])
-        .add_attribute("method", "remove_validator"))
+        .add_message(msg)
+        .add_attribute("method", "replace_validator")
+        .add_attribute("old_validator", old.to_string())
+        .add_attribute("new_validator", new.to_string())
+        .add_attribute("amount", delegation.amount.amount)
+        .add_attribute("accumulated_rewards", withdrawn_rewards))
+}
+
+fn claim_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    validators_to_claim: Option<Vec<Addr>>,
+    restake: bool,
+) -> Result<Response, ContractError> {
+    if !sender_has_role(deps.storage, &info.sender, Role::Admin)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut validators_to_claim = match validators_to_claim {
+        Some(validators_to_claim) => validators_to_claim,
+        None => validators()
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, validator)| validator.operator_address))
+            .collect::<StdResult<_>>()?,
+    };
+    // a second claim of the same validator would count its rewards twice
+    validators_to_claim.sort();
+    validators_to_claim.dedup();
+    let vault_denom = STATE.load(deps.storage)?.vault_denom;
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    for validator in validators_to_claim {
+        if validators().may_load(deps.storage, &validator)?.is_none() {
+            return Err(ContractError::ValidatorDoesNotExist {});
+        }
+        let delegation = deps
+            .querier
+            .query_delegation(&env.contract.address, &validator)?
+            .ok_or_else(|| ContractError::NoDelegationFound {
+                manager: env.contract.address.clone(),
+                validator: validator.clone(),
+            })?;
+
+        let mut baseline =
+            rebase_rewards_baseline(deps.storage, &validator, &delegation.accumulated_rewards)?;
+
+        msgs.push(CosmosMsg::Distribution(
+            DistributionMsg::WithdrawDelegatorReward {
+                validator: validator.to_string(),
+            },
+        ));
+        let restake_amount = delegation
+            .accumulated_rewards
+            .iter()
+            .find(|reward| reward.denom == vault_denom)
+            .map_or(Uint128::zero(), |reward| reward.amount);
+        if restake && !restake_amount.is_zero() {
+            baseline.restaked += restake_amount;
+            msgs.push(CosmosMsg::Staking(StakingMsg::Delegate {
+                validator: validator.to_string(),
+                amount: Coin::new(restake_amount.u128(), vault_denom.clone()),
+            }));
+        }
+        REWARDS_BASELINES.save(deps.storage, &validator, &baseline)?;
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("method", "claim_rewards"))
+}
+
+// Rewards earned since the baseline are moved into `claimed` and the baseline restarts at zero, as
+// the accumulated rewards are withdrawn by a claim, a delegation or an undelegation.
+fn rebase_rewards_baseline(
+    storage: &dyn Storage,
+    addr: &Addr,
+    accumulated_rewards: &[Coin],
+) -> StdResult<RewardsBaseline> {
+    let mut baseline = get_rewards_baseline(storage, addr, u64::MAX)?;
+    let (earned, _) = diff_rewards(accumulated_rewards, &baseline.coins);
+    baseline.claimed = add_coins(&baseline.claimed, &earned);
+    baseline.coins = vec![];
+    Ok(baseline)
+}
+
+// Our delegation without the principal we delegated or undelegated since the previous cron, so
+// that only slashing moves it away from the delegated amount of the previous metrics.
+fn get_delegation_before_principal_changes(
+    storage: &dyn Storage,
+    addr: &Addr,
+    delegated_amount: Uint128,
+) -> StdResult<Uint128> {
+    Ok(match REWARDS_BASELINES.may_load(storage, addr)? {
+        Some(baseline) => (delegated_amount + baseline.undelegated)
+            .saturating_sub(baseline.delegated + baseline.restaked),
+        None => delegated_amount,
+    })
+}
+
+// The baseline stored by a claim since the last cron, or the rewards of the latest metrics before
+// `timestamp`
+fn get_rewards_baseline(
+    storage: &dyn Storage,
+    addr: &Addr,
+    timestamp: u64,
+) -> StdResult<RewardsBaseline> {
+    if let Some(baseline) = REWARDS_BASELINES.may_load(storage, addr)? {
+        return Ok(baseline);
+    }
+    Ok(RewardsBaseline {
+        coins: get_previous_metrics(storage, addr, timestamp)?
+            .map(|metrics| metrics.rewards_in_coins)
+            .unwrap_or_default(),
+        claimed: vec![],
+        restaked: Uint128::zero(),
+        delegated: Uint128::zero(),
+        undelegated: Uint128::zero(),
+    })
+}
+
+fn add_coins(coins: &[Coin], other: &[Coin]) -> Vec<Coin> {
+    let mut sum = coins.to_vec();
+    for coin in other {
+        match sum.iter_mut().find(|c| c.denom == coin.denom) {
+            Some(existing) => existing.amount += coin.amount,
+            None => sum.push(coin.clone()),
+        }
+    }
+    sum
+}
+
+// Accumulated rewards only grow until they are withdrawn. When any denom went down, the rewards
+// were reset in between and everything accumulated now was earned since. Returns (diff, was reset).
+fn diff_rewards(current: &[Coin], baseline: &[Coin]) -> (Vec<Coin>, bool) {
+    let mut current_map: HashMap<&str, Uint128> = HashMap::new();
+    for coin in current {
+        current_map.insert(&coin.denom, coin.amount);
+    }
+
+    let reset = baseline.iter().any(|previous| {
+        current_map
+            .get(previous.denom.as_str())
+            .map_or(!previous.amount.is_zero(), |amount| {
+                *amount < previous.amount
+            })
+    });
+    if reset {
+        return (current.to_vec(), true);
+    }
+
+    let mut baseline_map: HashMap<&str, Uint128> = HashMap::new();
+    for coin in baseline {
+        baseline_map.insert(&coin.denom, coin.amount);
+    }
+    let diff = current
+        .iter()
+        .map(|coin| match baseline_map.get(coin.denom.as_str()) {
+            Some(baseline_amount) => Coin {
+                denom: coin.denom.clone(),
+                amount: coin.amount.sub(baseline_amount),
+            },
+            None => coin.clone(),
+        })
+        .collect();
+    (diff, false)
 }
 
 fn delete_metrics_for_validator(
@@ -526,7 +1694,7 @@ fn delete_metrics_for_validator(
     timestamp_start: usize,
     timestamp_count: usize,
 ) -> Result<Response, ContractError> {
-    if !sender_is_manager(&deps, &info) {
+    if !sender_has_role(deps.storage, &info.sender, Role::Pruner)? {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -558,58 +1726,246 @@ fn delete_metrics_for_validator(
         ))
 }
 
-// Anyone can call this but funds will only be sent back to manager.
+// Stakes are paid with the funds sent to AddValidator, so the whole balance can be spent
+fn get_available_balance(deps: Deps, env: &Env, denom: &str) -> StdResult<Uint128> {
+    Ok(deps
+        .querier
+        .query_balance(&env.contract.address, denom)?
+        .amount)
+}
+
+// Treasurers can withdraw to the manager or to a recipient the manager allowed.
 fn withdraw_funds(
     deps: DepsMut,
-    _info: MessageInfo,
+    env: Env,
+    info: MessageInfo,
     amount: Uint128,
+    denom: Option<String>,
+    recipient: Option<String>,
 ) -> Result<Response, ContractError> {
+    if !sender_has_role(deps.storage, &info.sender, Role::Treasurer)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
     let config = CONFIG.load(deps.storage)?;
     let state = STATE.load(deps.storage)?;
 
     if amount.is_zero() {
         return Err(ContractError::ZeroAmount {});
     }
-    Ok(Response::new().add_message(BankMsg::Send {
-        to_address: config.manager.to_string(),
-        amount: vec![Coin::new(amount.u128(), state.vault_denom)],
-    }))
+
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => config.manager.clone(),
+    };
+    if recipient != config.manager && !WITHDRAWAL_RECIPIENTS.has(deps.storage, &recipient) {
+        return Err(ContractError::RecipientNotAllowed {});
+    }
+
+    let denom = denom.unwrap_or_else(|| state.vault_denom.clone());
+    if amount > get_available_balance(deps.as_ref(), &env, &denom)? {
+        return Err(ContractError::InsufficientFunds {});
+    }
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin::new(amount.u128(), denom.clone())],
+        })
+        .add_attribute("method", "withdraw_funds")
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", Coin::new(amount.u128(), denom).to_string()))
 }
 
-pub fn record_validator_metrics(
+// Only the manager can change where funds may go, admins and treasurers can't widen it themselves
+fn allow_withdrawal_recipient(
     deps: DepsMut,
-    env: Env,
     info: MessageInfo,
-    timestamp: u64,
+    addr: String,
 ) -> Result<Response, ContractError> {
-    // can only be called by manager
-    if !sender_is_manager(&deps, &info) {
+    if info.sender != CONFIG.load(deps.storage)?.manager {
         return Err(ContractError::Unauthorized {});
     }
 
-    if get_last_recorded_timestamp(&deps) > timestamp {
-        return Err(ContractError::TimestampWithinExistingRange {});
+    let addr = deps.api.addr_validate(&addr)?;
+    if WITHDRAWAL_RECIPIENTS.has(deps.storage, &addr) {
+        return Err(ContractError::RecipientAlreadyAllowed {});
     }
+    WITHDRAWAL_RECIPIENTS.save(deps.storage, &addr, &true)?;
 
-    let validators_to_record = get_validators_to_record(deps.storage, timestamp)?;
+    Ok(Response::new()
+        .add_attribute("method", "allow_withdrawal_recipient")
+        .add_attribute("addr", addr))
+}
 
-    if validators_to_record.is_empty() {
-        return Ok(Response::new()
-            .add_attribute("method", "record_validator_metrics")
-            .add_attribute("msg", "All validators are recorded for the given cron time")
-            .add_attribute("validators_left", "0"));
+fn disallow_withdrawal_recipient(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    if info.sender != CONFIG.load(deps.storage)?.manager {
+        return Err(ContractError::Unauthorized {});
     }
 
-    let current_validators_metrics =
-        compute_current_metrics(&deps, env, &validators_to_record, timestamp)?;
-
-    let t = U64Key::new(timestamp);
-    for metric in current_validators_metrics {
-        METRICS_HISTORY.save(deps.storage, (&metric.operator_addr, t.clone()), &metric)?;
+    let addr = deps.api.addr_validate(&addr)?;
+    if !WITHDRAWAL_RECIPIENTS.has(deps.storage, &addr) {
+        return Err(ContractError::RecipientNotAllowed {});
     }
+    WITHDRAWAL_RECIPIENTS.remove(deps.storage, &addr);
 
     Ok(Response::new()
-        .add_attribute("method", "record_validator_metrics")
+        .add_attribute("method", "disallow_withdrawal_recipient")
+        .add_attribute("addr", addr))
+}
+
+// Voting power is not available through the staking querier, so it is recorded separately on top
+// of the metrics of a cron.
+pub fn record_voting_power(
+    deps: DepsMut,
+    info: MessageInfo,
+    timestamp: u64,
+    total_bonded: Uint128,
+    voting_powers: Vec<ValidatorVotingPower>,
+) -> Result<Response, ContractError> {
+    if !sender_has_role(deps.storage, &info.sender, Role::MetricsRecorder)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if total_bonded.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let total_tokens = voting_powers
+        .iter()
+        .try_fold(Uint128::zero(), |acc, voting_power| {
+            acc.checked_add(voting_power.tokens)
+        })
+        .map_err(StdError::from)?;
+    if total_tokens > total_bonded {
+        return Err(ContractError::VotingPowerAboveTotalBonded {});
+    }
+
+    let mut recorded = 0_u64;
+    let mut skipped = 0_u64;
+    for voting_power in voting_powers.iter() {
+        let key = (&voting_power.addr, U64Key::new(timestamp));
+        let mut metrics = match METRICS_HISTORY.may_load(deps.storage, key.clone())? {
+            Some(metrics) => metrics,
+            None => {
+                skipped += 1;
+                continue;
+            }
+        };
+        metrics.voting_power = Some(voting_power.tokens);
+        metrics.network_share = Some(Decimal::from_ratio(voting_power.tokens, total_bonded));
+        METRICS_HISTORY.save(deps.storage, key, &metrics)?;
+        recorded += 1;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "record_voting_power")
+        .add_attribute("timestamp", timestamp.to_string())
+        .add_attribute("validators_recorded", recorded.to_string())
+        .add_attribute("validators_skipped", skipped.to_string()))
+}
+
+// With a cron schedule the timestamp is the block time rounded down to the interval, so every batch
+// of a cron within the interval lands on the same timestamp. A supplied timestamp is only checked.
+fn resolve_cron_timestamp(
+    storage: &dyn Storage,
+    env: &Env,
+    timestamp: Option<u64>,
+) -> Result<u64, ContractError> {
+    let cron_schedule = match CONFIG.load(storage)?.cron_schedule {
+        Some(cron_schedule) => cron_schedule,
+        None => return timestamp.ok_or(ContractError::TimestampRequired {}),
+    };
+
+    let block_time = env.block.time.seconds();
+    if let Some(timestamp) = timestamp {
+        let drift = timestamp.max(block_time) - timestamp.min(block_time);
+        if drift > cron_schedule.tolerance {
+            return Err(ContractError::TimestampOutsideTolerance {});
+        }
+    }
+
+    Ok(block_time - block_time % cron_schedule.interval)
+}
+
+pub fn record_validator_metrics(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    timestamp: u64,
+) -> Result<Response, ContractError> {
+    let last_recorded_timestamp = get_last_recorded_timestamp(&deps);
+    let is_keeper = !sender_has_role(deps.storage, &info.sender, Role::MetricsRecorder)?;
+    if is_keeper {
+        check_keeper_can_record(deps.storage, last_recorded_timestamp, timestamp)?;
+    }
+
+    if last_recorded_timestamp > timestamp {
+        return Err(ContractError::TimestampWithinExistingRange {});
+    }
+
+    let mut response = Response::new();
+    if last_recorded_timestamp != timestamp
+        && is_cron_incomplete(deps.storage, last_recorded_timestamp)?
+    {
+        if CONFIG.load(deps.storage)?.refuse_new_cron_when_incomplete {
+            return Err(ContractError::CronIncomplete {
+                timestamp: last_recorded_timestamp,
+            });
+        }
+        response = response.add_event(
+            Event::new("incomplete_cron")
+                .add_attribute("timestamp", last_recorded_timestamp.to_string()),
+        );
+    }
+
+    let validators_to_record = get_validators_to_record(deps.storage, timestamp)?;
+
+    if validators_to_record.is_empty() {
+        return Ok(response
+            .add_attribute("method", "record_validator_metrics")
+            .add_attribute("msg", "All validators are recorded for the given cron time")
+            .add_attribute("validators_left", "0"));
+    }
+
+    let mut cron = match CRON_RECORDS.may_load(deps.storage, U64Key::new(timestamp))? {
+        Some(cron) => cron,
+        None => CronRecord {
+            timestamp,
+            total_validators: STATE.load(deps.storage)?.total_validators,
+            recorded: 0,
+            skipped: vec![],
+            started_at_height: env.block.height,
+            started_at_time: env.block.time.seconds(),
+            finished_at_height: None,
+            finished_at_time: None,
+            next_insertion_order: 0,
+            tipped_batches: 0,
+        },
+    };
+    cron.next_insertion_order = STATE.load(deps.storage)?.validator_index_for_next_cron;
+
+    let mut response = record_metrics_batch(
+        deps.branch(),
+        env.clone(),
+        timestamp,
+        &validators_to_record,
+        cron,
+        response,
+    )?;
+
+    if is_keeper {
+        if let Some(tip) = get_keeper_tip(deps, &env, &info.sender, timestamp)? {
+            response = response.add_message(tip);
+        }
+    }
+
+    Ok(response
+        .add_attribute("method", "record_validator_metrics")
         .add_attribute(
             "msg",
             format!(
@@ -619,26 +1975,400 @@ pub fn record_validator_metrics(
         ))
 }
 
+// Keepers are only let in with block time derived timestamps, so they can't pick the cron timestamp
+fn check_keeper_can_record(
+    storage: &dyn Storage,
+    last_recorded_timestamp: u64,
+    timestamp: u64,
+) -> Result<(), ContractError> {
+    let config = CONFIG.load(storage)?;
+    let cron_schedule = match (config.keeper_incentive, config.cron_schedule) {
+        (Some(_), Some(cron_schedule)) => cron_schedule,
+        _ => return Err(ContractError::Unauthorized {}),
+    };
+
+    // continuing the current cron is always allowed
+    if last_recorded_timestamp != 0
+        && timestamp != last_recorded_timestamp
+        && timestamp < last_recorded_timestamp + cron_schedule.interval
+    {
+        return Err(ContractError::CronIntervalNotElapsed {});
+    }
+    Ok(())
+}
+
+// Tips are paid per recorded batch, up to the configured number of batches per cron. Running out of
+// funds for tips doesn't fail the recording.
+fn get_keeper_tip(
+    deps: DepsMut,
+    env: &Env,
+    keeper: &Addr,
+    timestamp: u64,
+) -> StdResult<Option<BankMsg>> {
+    let keeper_incentive = match CONFIG.load(deps.storage)?.keeper_incentive {
+        Some(keeper_incentive) => keeper_incentive,
+        None => return Ok(None),
+    };
+    let mut cron = CRON_RECORDS.load(deps.storage, U64Key::new(timestamp))?;
+    if keeper_incentive.tip_per_batch.is_zero()
+        || cron.tipped_batches >= keeper_incentive.max_tipped_batches_per_cron
+    {
+        return Ok(None);
+    }
+
+    let vault_denom = STATE.load(deps.storage)?.vault_denom;
+    if get_available_balance(deps.as_ref(), env, &vault_denom)? < keeper_incentive.tip_per_batch {
+        return Ok(None);
+    }
+
+    cron.tipped_batches += 1;
+    CRON_RECORDS.save(deps.storage, U64Key::new(timestamp), &cron)?;
+
+    Ok(Some(BankMsg::Send {
+        to_address: keeper.to_string(),
+        amount: vec![Coin::new(
+            keeper_incentive.tip_per_batch.u128(),
+            vault_denom,
+        )],
+    }))
+}
+
+// Metrics can only be computed from the current chain state, so validators that a later cron has
+// already recorded can't be back filled and are left out.
+pub fn resume_cron(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    timestamp: u64,
+) -> Result<Response, ContractError> {
+    if !sender_has_role(deps.storage, &info.sender, Role::MetricsRecorder)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut cron = CRON_RECORDS
+        .may_load(deps.storage, U64Key::new(timestamp))?
+        .ok_or(ContractError::InvalidTimestamp {})?;
+    if cron.finished_at_height.is_some() {
+        return Err(ContractError::CronAlreadyComplete {});
+    }
+
+    let batch_size = CONFIG.load(deps.storage)?.batch_size;
+    let validators_batch: Vec<ValidatorAccounts> = validators()
+        .idx
+        .insertion_order
+        .range(
+            deps.storage,
+            Some(Bound::Inclusive(
+                U64Key::new(cron.next_insertion_order).into(),
+            )),
+            None,
+            Order::Ascending,
+        )
+        .take(batch_size as usize)
+        .map(|item| item.map(|(_, validator)| validator))
+        .collect::<StdResult<_>>()?;
+    if let Some(last) = validators_batch.last() {
+        cron.next_insertion_order = last.insertion_order + 1;
+        // RecordMetrics continues the current cron from the state cursor, keep it in sync
+        let state = STATE.load(deps.storage)?;
+        if state.cron_timestamps.last() == Some(&timestamp) {
+            STATE.update(deps.storage, |mut s| -> StdResult<_> {
+                s.validator_index_for_next_cron = cron.next_insertion_order;
+                Ok(s)
+            })?;
+        }
+    }
+
+    let mut validators_to_record = vec![];
+    let mut validators_recorded_later = 0;
+    for validator in validators_batch {
+        if has_metrics_since(deps.storage, &validator.operator_address, timestamp)? {
+            validators_recorded_later += 1;
+        } else {
+            validators_to_record.push(validator);
+        }
+    }
+
+    let response = record_metrics_batch(
+        deps,
+        env,
+        timestamp,
+        &validators_to_record,
+        cron,
+        Response::new(),
+    )?;
+
+    Ok(response
+        .add_attribute("method", "resume_cron")
+        .add_attribute("timestamp", timestamp.to_string())
+        .add_attribute(
+            "validators_recorded",
+            validators_to_record.len().to_string(),
+        )
+        .add_attribute(
+            "validators_recorded_later",
+            validators_recorded_later.to_string(),
+        ))
+}
+
+// Records the metrics of a batch of validators for the cron and updates its progress.
+// `cron.next_insertion_order` must already point past the batch.
+fn record_metrics_batch(
+    deps: DepsMut,
+    env: Env,
+    timestamp: u64,
+    validators_to_record: &[ValidatorAccounts],
+    mut cron: CronRecord,
+    mut response: Response,
+) -> Result<Response, ContractError> {
+    let (height, block_time) = (env.block.height, env.block.time.seconds());
+    let (current_validators_metrics, current_validators_status) =
+        compute_current_metrics(&deps, env, validators_to_record, timestamp)?;
+
+    for (validator, status) in current_validators_status {
+        if status != ValidatorStatus::Active {
+            cron.skipped.push(SkippedValidator {
+                addr: validator.clone(),
+                reason: status.clone(),
+            });
+        }
+        STATUS_HISTORY.save(
+            deps.storage,
+            (&validator, U64Key::new(timestamp)),
+            &ValidatorStatusRecord { timestamp, status },
+        )?;
+    }
+    cron.recorded += current_validators_metrics.len() as u64;
+
+    let commission_change_threshold = CONFIG.load(deps.storage)?.commission_change_threshold;
+    let t = U64Key::new(timestamp);
+    let mut aggregate = TIMESTAMP_AGGREGATES
+        .may_load(deps.storage, t.clone())?
+        .unwrap_or(TimestampAggregate {
+            timestamp,
+            ..Default::default()
+        });
+    for metric in current_validators_metrics {
+        add_to_timestamp_aggregate(deps.storage, &mut aggregate, &metric)?;
+        if let Some(change) = detect_commission_change(deps.storage, &metric)? {
+            commission_changes().save(deps.storage, (t.clone(), &change.validator), &change)?;
+            response = response.add_event(
+                Event::new("commission_change")
+                    .add_attribute("validator", change.validator.to_string())
+                    .add_attribute("timestamp", change.timestamp.to_string())
+                    .add_attribute(
+                        "previous_commission",
+                        change.previous_commission.to_string(),
+                    )
+                    .add_attribute("new_commission", change.new_commission.to_string())
+                    .add_attribute(
+                        "above_threshold",
+                        is_commission_change_above_threshold(&change, commission_change_threshold)
+                            .to_string(),
+                    ),
+            );
+        }
+        if let Some(event) = detect_slashing_event(deps.storage, &metric)? {
+            slashing_events().save(deps.storage, (t.clone(), &event.validator), &event)?;
+            response = response.add_event(
+                Event::new("slashing")
+                    .add_attribute("validator", event.validator.to_string())
+                    .add_attribute("timestamp", event.timestamp.to_string())
+                    .add_attribute("ratio", event.ratio.to_string())
+                    .add_attribute("amount_lost", event.amount_lost),
+            );
+        }
+        if metric.rewards_reset {
+            response = response.add_event(
+                Event::new("rewards_reset")
+                    .add_attribute("validator", metric.operator_addr.to_string())
+                    .add_attribute("timestamp", metric.timestamp.to_string()),
+            );
+        }
+        METRICS_HISTORY.save(deps.storage, (&metric.operator_addr, t.clone()), &metric)?;
+        REWARDS_BASELINES.remove(deps.storage, &metric.operator_addr);
+    }
+    TIMESTAMP_AGGREGATES.save(deps.storage, t.clone(), &aggregate)?;
+
+    if !has_validators_left_to_record(deps.storage, cron.next_insertion_order)? {
+        cron.finished_at_height = Some(height);
+        cron.finished_at_time = Some(block_time);
+    }
+    CRON_RECORDS.save(deps.storage, t, &cron)?;
+
+    Ok(response)
+}
+
+// Crons recorded before progress was tracked have no record and are taken as complete
+fn is_cron_incomplete(storage: &dyn Storage, timestamp: u64) -> StdResult<bool> {
+    let cron_opt = CRON_RECORDS.may_load(storage, U64Key::new(timestamp))?;
+    Ok(matches!(cron_opt, Some(cron) if cron.finished_at_height.is_none()))
+}
+
+fn has_metrics_since(storage: &dyn Storage, addr: &Addr, timestamp: u64) -> StdResult<bool> {
+    Ok(METRICS_HISTORY
+        .prefix(addr)
+        .range(
+            storage,
+            Some(Bound::Inclusive(U64Key::new(timestamp).into())),
+            None,
+            Order::Ascending,
+        )
+        .next()
+        .is_some())
+}
+
+fn has_validators_left_to_record(storage: &dyn Storage, next: u64) -> StdResult<bool> {
+    Ok(validators()
+        .idx
+        .insertion_order
+        .range(
+            storage,
+            Some(Bound::Inclusive(U64Key::new(next).into())),
+            None,
+            Order::Ascending,
+        )
+        .next()
+        .is_some())
+}
+
+// Must be called before `metric` is saved, so the previous record is the one of an earlier cron
+fn add_to_timestamp_aggregate(
+    storage: &dyn Storage,
+    aggregate: &mut TimestampAggregate,
+    metric: &ValidatorMetrics,
+) -> StdResult<()> {
+    aggregate.validators_recorded += 1;
+    aggregate.total_delegated += metric.delegated_amount;
+    aggregate.total_rewards = decimal_summation_in_256(aggregate.total_rewards, metric.rewards);
+    aggregate.stake_weighted_commission_sum = decimal_summation_in_256(
+        aggregate.stake_weighted_commission_sum,
+        decimal_multiplication_in_256(
+            metric.commission,
+            uint128_to_decimal(metric.delegated_amount),
+        ),
+    );
+
+    if let Some(apr) = compute_apr_against_previous(storage, metric)? {
+        aggregate.apr_count += 1;
+        aggregate.apr_sum = decimal_summation_in_256(aggregate.apr_sum, apr);
+        aggregate.min_apr = Some(aggregate.min_apr.map_or(apr, |min_apr| min(min_apr, apr)));
+        aggregate.max_apr = Some(aggregate.max_apr.map_or(apr, |max_apr| max(max_apr, apr)));
+    }
+    Ok(())
+}
+
+// apr can't be computed against a zero delegation, such validators are left out of the stats
+fn compute_apr_against_previous(
+    storage: &dyn Storage,
+    metric: &ValidatorMetrics,
+) -> StdResult<Option<Decimal>> {
+    match get_previous_metrics(storage, &metric.operator_addr, metric.timestamp)? {
+        Some(previous) if !previous.delegated_amount.is_zero() => Ok(Some(compute_apr(
+            &previous,
+            metric,
+            metric.timestamp - previous.timestamp,
+        )?)),
+        _ => Ok(None),
+    }
+}
+
+// Latest metrics recorded for the validator strictly before the timestamp
+fn get_previous_metrics(
+    storage: &dyn Storage,
+    addr: &Addr,
+    timestamp: u64,
+) -> StdResult<Option<ValidatorMetrics>> {
+    Ok(METRICS_HISTORY
+        .prefix(addr)
+        .range(
+            storage,
+            None,
+            Some(Bound::Exclusive(U64Key::new(timestamp).into())),
+            Order::Descending,
+        )
+        .next()
+        .transpose()?
+        .map(|(_, metric)| metric))
+}
+
+// The slashing pointer only goes down when our delegation shrinks, i.e. on slashing
+fn detect_slashing_event(
+    storage: &dyn Storage,
+    metric: &ValidatorMetrics,
+) -> StdResult<Option<SlashingEvent>> {
+    let previous = match get_previous_metrics(storage, &metric.operator_addr, metric.timestamp)? {
+        Some(previous) => previous,
+        None => return Ok(None),
+    };
+
+    if previous.slashing_pointer.is_zero() || metric.slashing_pointer >= previous.slashing_pointer {
+        return Ok(None);
+    }
+
+    Ok(Some(SlashingEvent {
+        validator: metric.operator_addr.clone(),
+        timestamp: metric.timestamp,
+        ratio: decimal_division_in_256(metric.slashing_pointer, previous.slashing_pointer),
+        amount_lost: previous
+            .delegated_amount
+            .saturating_sub(metric.delegated_amount),
+    }))
+}
+
+fn detect_commission_change(
+    storage: &dyn Storage,
+    metric: &ValidatorMetrics,
+) -> StdResult<Option<CommissionChange>> {
+    let previous = match get_previous_metrics(storage, &metric.operator_addr, metric.timestamp)? {
+        Some(previous) => previous,
+        None => return Ok(None),
+    };
+
+    if previous.commission == metric.commission {
+        return Ok(None);
+    }
+
+    Ok(Some(CommissionChange {
+        validator: metric.operator_addr.clone(),
+        timestamp: metric.timestamp,
+        previous_commission: previous.commission,
+        new_commission: metric.commission,
+    }))
+}
+
+fn is_commission_change_above_threshold(change: &CommissionChange, threshold: Decimal) -> bool {
+    let diff = if change.new_commission > change.previous_commission {
+        decimal_subtraction_in_256(change.new_commission, change.previous_commission)
+    } else {
+        decimal_subtraction_in_256(change.previous_commission, change.new_commission)
+    };
+    diff > threshold
+}
+
 fn get_last_recorded_timestamp(deps: &DepsMut) -> u64 {
     let state = STATE.load(deps.storage).unwrap();
     *state.cron_timestamps.last().unwrap_or(&(0_u64))
 }
 
+type ValidatorsStatus = Vec<(Addr, ValidatorStatus)>;
+
+// Returns the metrics of validators in the active set and the status of every validator
 fn compute_current_metrics(
     deps: &DepsMut,
     env: Env,
     validators: &[ValidatorAccounts],
     timestamp: u64,
-) -> Result<Vec<ValidatorMetrics>, ContractError> {
+) -> Result<(Vec<ValidatorMetrics>, ValidatorsStatus), ContractError> {
     let state = STATE.load(deps.storage)?;
     let vault_denom = state.vault_denom;
-    let last_cron_time_opt = state.cron_timestamps.last();
 
     let mut exchange_rates_map: HashMap<String, Decimal> = HashMap::new();
     exchange_rates_map.insert(vault_denom.clone(), Decimal::one());
     let querier = TerraQuerier::new(&deps.querier);
 
     let mut current_metrics: Vec<ValidatorMetrics> = vec![];
+    let mut current_status: ValidatorsStatus = vec![];
 
     for validator_addr in validators {
         // could be extracted into another function? computes metric for validator
@@ -660,16 +2390,37 @@ fn compute_current_metrics(
             .query_validator(validator_addr.operator_address.clone())?;
         // if suddenly validators drop out of the validator set, either due to jailing or some other mishap.
         if validator_opt.is_none() {
+            let previous_metrics_opt =
+                get_previous_metrics(deps.storage, &validator_addr.operator_address, timestamp)?;
+            let delegated_amount = get_delegation_before_principal_changes(
+                deps.storage,
+                &validator_addr.operator_address,
+                delegation_opt.as_ref().unwrap().amount.amount,
+            )?;
+            let slashed = matches!(previous_metrics_opt, Some(previous)
+                if delegated_amount < previous.delegated_amount);
+            current_status.push((
+                validator_addr.operator_address.clone(),
+                if slashed {
+                    ValidatorStatus::Jailed
+                } else {
+                    ValidatorStatus::MissingFromSet
+                },
+            ));
             continue;
         } // Handle this edge case when validator previous timestamp does not match state.cron_timings.last() entry.
 
         let validator = validator_opt.unwrap();
         let delegation = delegation_opt.unwrap();
+        current_status.push((
+            validator_addr.operator_address.clone(),
+            ValidatorStatus::Active,
+        ));
 
-        let (rewards_diff, previous_rewards) = get_diff_in_rewards_from_last_cron(
+        let (rewards_diff, previous_rewards, rewards_reset) = get_diff_in_rewards_from_last_cron(
             deps,
             &&(validator_addr.operator_address),
-            last_cron_time_opt,
+            timestamp,
             delegation.accumulated_rewards.clone(),
         )?;
 
@@ -682,6 +2433,12 @@ fn compute_current_metrics(
 
         // This is the new Delegated amount after slashing Ex: (10 => 9.8 etc.,)
         let current_delegated_amount = delegation.amount.amount;
+        // our own delegations and undelegations aren't a change in the slashing pointer
+        let delegation_before_principal_changes = get_delegation_before_principal_changes(
+            deps.storage,
+            &validator_addr.operator_address,
+            current_delegated_amount,
+        )?;
 
         let self_delegation_opt = deps.querier.query_delegation(
             validator_addr.account_address.clone(), //This is the Account Address
@@ -707,7 +2464,7 @@ fn compute_current_metrics(
         if !vector_delegation_change_ratio.is_empty() {
             let delegation_change_ratio = &vector_delegation_change_ratio.last().unwrap().1;
             current_slashing_pointer = decimal_division_in_256(
-                uint128_to_decimal(current_delegated_amount),
+                uint128_to_decimal(delegation_before_principal_changes),
                 uint128_to_decimal(delegation_change_ratio.delegated_amount),
             );
             current_slashing_pointer = decimal_multiplication_in_256(
@@ -725,55 +2482,34 @@ fn compute_current_metrics(
             commission: validator.commission,
             max_commission: validator.max_commission,
             rewards_in_coins: delegation.accumulated_rewards.clone(),
+            voting_power: None,
+            network_share: None,
+            block_height: env.block.height,
+            block_time: env.block.time.seconds(),
+            rewards_reset,
             timestamp,
         });
     }
-    Ok(current_metrics)
+    Ok((current_metrics, current_status))
 }
 
 fn get_diff_in_rewards_from_last_cron(
     deps: &DepsMut,
     validator_addr: &&Addr,
-    last_cron_time_opt: Option<&u64>,
+    timestamp: u64,
     current_accumulated_rewards: Vec<Coin>,
-    // Return is Tuple of (Vec<Coin> = Diff in rewards,  Decimal = Previous cron rewards)
-) -> Result<(Vec<Coin>, Decimal), ContractError> {
-    // If this is the very first cron we simply return current_accumulated_rewards as the diff
-    if last_cron_time_opt.is_none() {
-        return Ok((current_accumulated_rewards, Decimal::zero()));
-    }
-
-    let last_cron_time = last_cron_time_opt.unwrap();
-    let previous_metrics_opt =
-        METRICS_HISTORY.may_load(deps.storage, (validator_addr, U64Key::new(*last_cron_time)))?;
-
+    // Return is Tuple of (Vec<Coin> = Diff in rewards,  Decimal = Previous cron rewards, bool = rewards were reset)
+) -> Result<(Vec<Coin>, Decimal, bool), ContractError> {
     // If validator is added after the previous cron run, then there wont be any prev history for this validator
-    if previous_metrics_opt.is_none() {
-        return Ok((current_accumulated_rewards, Decimal::zero()));
-    }
-
-    let previous_metrics = previous_metrics_opt.unwrap();
-
-    let mut previous_rewards_map: HashMap<String, Uint128> = HashMap::new();
-    for reward in previous_metrics.rewards_in_coins {
-        previous_rewards_map.insert(reward.denom, reward.amount);
-    }
-
-    let diff_in_rewards = current_accumulated_rewards
-        .into_iter()
-        .map(|reward| {
-            // Find matching denom reward in the previous run
-            let prev_reward_opt = previous_rewards_map.get(&reward.denom);
-            match prev_reward_opt {
-                Some(prev_reward) => Coin {
-                    denom: reward.denom,
-                    amount: reward.amount.sub(prev_reward), // Subtract the previous denom reward amount with the current
-                },
-                None => reward, // Last rewards vec does not contain this particular denom reward
-            }
-        })
-        .collect();
-    Ok((diff_in_rewards, previous_metrics.rewards))
+    let previous_rewards = get_previous_metrics(deps.storage, validator_addr, timestamp)?
+        .map_or(Decimal::zero(), |previous_metrics| previous_metrics.rewards);
+
+    // Rewards claimed since the previous cron are added back on top of what accumulated since the claim
+    let baseline = get_rewards_baseline(deps.storage, validator_addr, timestamp)?;
+    let (diff_since_baseline, rewards_reset) =
+        diff_rewards(&current_accumulated_rewards, &baseline.coins);
+    let diff_in_rewards = add_coins(&diff_since_baseline, &baseline.claimed);
+    Ok((diff_in_rewards, previous_rewards, rewards_reset))
 }
 
 fn get_total_rewards_in_vault_denom(
@@ -803,8 +2539,6 @@ fn get_validators_to_record(
     let batch_size = CONFIG.load(storage)?.batch_size;
     let state = STATE.load(storage)?;
     let last_cron_time_opt = state.cron_timestamps.last();
-    let validators = state.validators;
-    let total_validators: u64 = validators.len() as u64;
     let mut validator_index_for_next_cron = state.validator_index_for_next_cron;
 
     // If the Cron time is completely New (Update State)
@@ -818,22 +2552,24 @@ fn get_validators_to_record(
         validator_index_for_next_cron = 0
     }
 
-    if validator_index_for_next_cron.ge(&total_validators) {
+    // validators are walked in insertion order, the cursor is the insertion order of the next
+    // validator to record, so removing a validator mid cron doesn't shift the remaining ones.
+    let start = Some(Bound::Inclusive(
+        U64Key::new(validator_index_for_next_cron).into(),
+    ));
+    let validators_batch: Vec<ValidatorAccounts> = validators()
+        .idx
+        .insertion_order
+        .range(storage, start, None, Order::Ascending)
+        .take(batch_size as usize)
+        .map(|item| item.map(|(_, validator)| validator))
+        .collect::<StdResult<_>>()?;
+
+    if validators_batch.is_empty() {
         return Ok(vec![]);
     }
 
-    // Examples
-    // len = 12, batch_size = 5
-    // (=0 <5) 5 (=5 <10) 10 (=10 <12) Final(=12 is >=12 (total_validators)) => return Ok(vec![]);
-    // len = 2, batch_size = 1
-    // (=0 <1) 1 (=1 <2) Final(=2 is >= 2 (total_validators)) => return Ok(vec![]);
-    let start = validator_index_for_next_cron;
-    let end = cmp::min(start + batch_size, total_validators);
-
-    let validators_batch: Vec<ValidatorAccounts> =
-        validators[(start as usize)..(end as usize)].to_vec();
-    // let validators_acc_batch = validators_acc[(start as usize)..(end as usize)].to_vec();
-
+    let end = validators_batch.last().unwrap().insertion_order + 1;
     STATE.update(storage, |mut s| -> StdResult<_> {
         s.validator_index_for_next_cron = end;
         Ok(s)
@@ -895,68 +2631,358 @@ fn query_config(deps: Deps) -> StdResult<Config> {
     Ok(config)
 }
 
-fn query_all_validator_metrics(
+// Events of a timestamp are never split across pages, so a page can go over the limit
+fn query_slashing_events(
     deps: Deps,
-    addr: Addr,
-) -> StdResult<Vec<(Vec<u8>, ValidatorMetrics)>> {
-    METRICS_HISTORY
-        .prefix(&addr)
-        .range(deps.storage, None, None, Order::Ascending)
-        .collect()
+    validator: Option<Addr>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<SlashingEvent>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // an empty address sorts before every other address of the next timestamp
+    let empty_addr = Addr::unchecked("");
+    let start = match start_after {
+        // nothing can come after the last possible timestamp
+        Some(timestamp) => match timestamp.checked_add(1) {
+            Some(next) => Some(Bound::Inclusive(
+                (U64Key::new(next), &empty_addr).joined_key(),
+            )),
+            None => return Ok(vec![]),
+        },
+        None => None,
+    };
+
+    let events = match &validator {
+        Some(validator) => slashing_events()
+            .idx
+            .validator
+            .prefix(validator.as_bytes().to_vec())
+            .range(deps.storage, start, None, Order::Ascending),
+        None => slashing_events().range(deps.storage, start, None, Order::Ascending),
+    };
+
+    let mut res: Vec<SlashingEvent> = vec![];
+    for item in events {
+        let (_, event) = item?;
+        if res.len() >= limit && res.last().unwrap().timestamp != event.timestamp {
+            break;
+        }
+        res.push(event);
+    }
+    Ok(res)
 }
 
-fn query_all_validator_metrics_btw_timestamps(
+// Changes of a timestamp are never split across pages, so a page can go over the limit
+fn query_commission_changes(
     deps: Deps,
-    addr: Addr,
-    timestamp1: u64,
-    timestamp2: u64,
-) -> StdResult<Vec<(Vec<u8>, ValidatorMetrics)>> {
-    if timestamp1.ge(&timestamp2) {
-        return Err(StdError::GenericErr {
-            msg: "timestamp1 cannot be greater than or equal to timestamp2".to_string(),
+    validator: Option<Addr>,
+    since: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<CommissionChangeResponse>> {
+    let threshold = CONFIG.load(deps.storage)?.commission_change_threshold;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let empty_addr = Addr::unchecked("");
+    let start =
+        since.map(|timestamp| Bound::Inclusive((U64Key::new(timestamp), &empty_addr).joined_key()));
+
+    let changes = match &validator {
+        Some(validator) => commission_changes()
+            .idx
+            .validator
+            .prefix(validator.as_bytes().to_vec())
+            .range(deps.storage, start, None, Order::Ascending),
+        None => commission_changes().range(deps.storage, start, None, Order::Ascending),
+    };
+
+    let mut res: Vec<CommissionChangeResponse> = vec![];
+    for item in changes {
+        let (_, change) = item?;
+        if res.len() >= limit && res.last().unwrap().timestamp != change.timestamp {
+            break;
+        }
+        res.push(CommissionChangeResponse {
+            above_threshold: is_commission_change_above_threshold(&change, threshold),
+            validator: change.validator,
+            timestamp: change.timestamp,
+            previous_commission: change.previous_commission,
+            new_commission: change.new_commission,
         });
     }
-    let from = Some(Bound::Inclusive(U64Key::new(timestamp1).into()));
-    let to = Some(Bound::Inclusive(U64Key::new(timestamp2).into()));
-
-    METRICS_HISTORY
-        .prefix(&addr)
-        .range(deps.storage, from, to, Order::Ascending)
-        .collect()
+    Ok(res)
 }
 
-fn query_validator_metrics_by_timestamp(
+fn query_validator_status_history(
     deps: Deps,
     addr: Addr,
-    timestamp: u64,
-) -> StdResult<ValidatorMetrics> {
-    METRICS_HISTORY.load(deps.storage, (&addr, U64Key::new(timestamp)))
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ValidatorStatusRecord>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|timestamp| Bound::Exclusive(U64Key::new(timestamp).into()));
+
+    STATUS_HISTORY
+        .prefix(&addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, record)| record))
+        .collect()
 }
 
-fn query_validators_metrics_by_timestamp(
+fn query_voting_power_history(
     deps: Deps,
-    timestamp: u64,
+    addr: Addr,
     from: u64,
     to: u64,
-) -> StdResult<Vec<ValidatorMetrics>> {
-    let validators = STATE.load(deps.storage)?.validators;
-    let mut validator_operator_addr = vec![];
-    for validator_addr_info in validators {
-        validator_operator_addr.push(validator_addr_info.operator_address.clone());
+) -> StdResult<Vec<VotingPowerRecord>> {
+    if from > to {
+        return Err(StdError::generic_err("from cannot be greater than to"));
     }
+    let from = Some(Bound::Inclusive(U64Key::new(from).into()));
+    let to = Some(Bound::Inclusive(U64Key::new(to).into()));
 
-    let total_validators: u64 = validator_operator_addr.len() as u64;
+    METRICS_HISTORY
+        .prefix(&addr)
+        .range(deps.storage, from, to, Order::Ascending)
+        .map(|item| {
+            item.map(|(_, metrics)| VotingPowerRecord {
+                timestamp: metrics.timestamp,
+                voting_power: metrics.voting_power,
+                network_share: metrics.network_share,
+            })
+        })
+        .collect()
+}
 
-    if to.ge(&total_validators) || from > to {
-        return Err(StdError::GenericErr {
-            msg: "Invalid indexes!".to_string(),
-        });
-    }
+fn query_timestamp_aggregate(deps: Deps, timestamp: u64) -> StdResult<TimestampAggregateResponse> {
+    let aggregate = TIMESTAMP_AGGREGATES.load(deps.storage, U64Key::new(timestamp))?;
 
-    let mut start = from;
-    let mut res: Vec<ValidatorMetrics> = vec![];
-    while start.le(&to) {
-        // note: this change will help with missing validator / timestamps due to deletion.
+    let mean_apr = if aggregate.apr_count == 0 {
+        None
+    } else {
+        Some(decimal_division_in_256(
+            aggregate.apr_sum,
+            u64_to_decimal(aggregate.apr_count),
+        ))
+    };
+    let stake_weighted_commission = if aggregate.total_delegated.is_zero() {
+        Decimal::zero()
+    } else {
+        decimal_division_in_256(
+            aggregate.stake_weighted_commission_sum,
+            uint128_to_decimal(aggregate.total_delegated),
+        )
+    };
+
+    Ok(TimestampAggregateResponse {
+        timestamp: aggregate.timestamp,
+        validators_recorded: aggregate.validators_recorded,
+        validators_with_apr: aggregate.apr_count,
+        mean_apr,
+        median_apr: compute_median(&query_timestamp_aprs(deps, timestamp)?),
+        min_apr: aggregate.min_apr,
+        max_apr: aggregate.max_apr,
+        total_delegated: aggregate.total_delegated,
+        total_rewards: aggregate.total_rewards,
+        stake_weighted_commission,
+    })
+}
+
+// Sorted aprs of the validators registered with a record at the timestamp, removed validators are
+// only part of the stored stats
+fn query_timestamp_aprs(deps: Deps, timestamp: u64) -> StdResult<Vec<Decimal>> {
+    let mut aprs = vec![];
+    for item in validators().range(deps.storage, None, None, Order::Ascending) {
+        let (_, validator) = item?;
+        let metric_opt = METRICS_HISTORY.may_load(
+            deps.storage,
+            (&validator.operator_address, U64Key::new(timestamp)),
+        )?;
+        if let Some(metric) = metric_opt {
+            if let Some(apr) = compute_apr_against_previous(deps.storage, &metric)? {
+                aprs.push(apr);
+            }
+        }
+    }
+    aprs.sort();
+    Ok(aprs)
+}
+
+fn query_cron_status(deps: Deps, timestamp: u64) -> StdResult<CronStatusResponse> {
+    let cron = CRON_RECORDS.load(deps.storage, U64Key::new(timestamp))?;
+    Ok(cron_status_response(cron))
+}
+
+fn query_incomplete_crons(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<CronStatusResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|timestamp| Bound::Exclusive(U64Key::new(timestamp).into()));
+
+    CRON_RECORDS
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref()
+                .map_or(true, |(_, cron)| cron.finished_at_height.is_none())
+        })
+        .take(limit)
+        .map(|item| item.map(|(_, cron)| cron_status_response(cron)))
+        .collect()
+}
+
+fn cron_status_response(cron: CronRecord) -> CronStatusResponse {
+    CronStatusResponse {
+        timestamp: cron.timestamp,
+        total_validators: cron.total_validators,
+        recorded: cron.recorded,
+        skipped: cron.skipped,
+        started_at_height: cron.started_at_height,
+        started_at_time: cron.started_at_time,
+        finished_at_height: cron.finished_at_height,
+        finished_at_time: cron.finished_at_time,
+        is_complete: cron.finished_at_height.is_some(),
+    }
+}
+
+fn query_pending_unbondings(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PendingUnbondingsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|id| Bound::Exclusive(U64Key::new(id).into()));
+
+    let unbondings = UNBONDINGS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, entry)| entry))
+        .collect::<StdResult<_>>()?;
+
+    Ok(PendingUnbondingsResponse {
+        unbondings,
+        total_unbonding: STATE.load(deps.storage)?.total_unbonding,
+    })
+}
+
+fn query_withdrawal_recipients(deps: Deps) -> StdResult<Vec<Addr>> {
+    Ok(WITHDRAWAL_RECIPIENTS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(conversion_utils::addr_from_vec_u8)
+        .collect())
+}
+
+fn query_roles(deps: Deps, addr: Option<Addr>) -> StdResult<Vec<RoleGrant>> {
+    let mut grants = vec![];
+    for role in Role::all() {
+        let members: Vec<Addr> = ROLES
+            .prefix(role.as_str())
+            .keys(deps.storage, None, None, Order::Ascending)
+            .map(conversion_utils::addr_from_vec_u8)
+            .collect();
+        for member in members {
+            if addr.is_none() || addr.as_ref().unwrap().eq(&member) {
+                grants.push(RoleGrant {
+                    role: role.clone(),
+                    addr: member,
+                });
+            }
+        }
+    }
+    Ok(grants)
+}
+
+fn query_all_validator_metrics(
+    deps: Deps,
+    addr: Addr,
+) -> StdResult<Vec<(Vec<u8>, ValidatorMetrics)>> {
+    METRICS_HISTORY
+        .prefix(&addr)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect()
+}
+
+fn query_all_validator_metrics_btw_timestamps(
+    deps: Deps,
+    addr: Addr,
+    timestamp1: u64,
+    timestamp2: u64,
+) -> StdResult<Vec<(Vec<u8>, ValidatorMetrics)>> {
+    if timestamp1.ge(&timestamp2) {
+        return Err(StdError::GenericErr {
+            msg: "timestamp1 cannot be greater than or equal to timestamp2".to_string(),
+        });
+    }
+    let from = Some(Bound::Inclusive(U64Key::new(timestamp1).into()));
+    let to = Some(Bound::Inclusive(U64Key::new(timestamp2).into()));
+
+    METRICS_HISTORY
+        .prefix(&addr)
+        .range(deps.storage, from, to, Order::Ascending)
+        .collect()
+}
+
+// Metrics are keyed by timestamp, so every record of the validator after `start_after` is scanned
+fn query_validator_metrics_by_height(
+    deps: Deps,
+    addr: Addr,
+    from_height: u64,
+    to_height: u64,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ValidatorMetrics>> {
+    if from_height > to_height {
+        return Err(StdError::generic_err(
+            "from_height cannot be greater than to_height",
+        ));
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|timestamp| Bound::Exclusive(U64Key::new(timestamp).into()));
+
+    METRICS_HISTORY
+        .prefix(&addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref().map_or(true, |(_, metrics)| {
+                metrics.block_height >= from_height && metrics.block_height <= to_height
+            })
+        })
+        .take(limit)
+        .map(|item| item.map(|(_, metrics)| metrics))
+        .collect()
+}
+
+// The returned metrics carry the timestamp that was actually resolved
+fn query_validator_metrics_by_timestamp(
+    deps: Deps,
+    addr: Addr,
+    timestamp: u64,
+    resolution: TimestampResolution,
+) -> StdResult<ValidatorMetrics> {
+    resolve_metrics(deps.storage, &addr, timestamp, &resolution)?
+        .ok_or_else(|| StdError::not_found("ValidatorMetrics"))
+}
+
+fn query_validators_metrics_by_timestamp(
+    deps: Deps,
+    timestamp: u64,
+    from: u64,
+    to: u64,
+) -> StdResult<Vec<ValidatorMetrics>> {
+    let total_validators: u64 = STATE.load(deps.storage)?.total_validators;
+
+    if to.ge(&total_validators) || from > to {
+        return Err(StdError::GenericErr {
+            msg: "Invalid indexes!".to_string(),
+        });
+    }
+
+    let validators =
+        get_validators_by_position(deps.storage, from as usize, (to - from + 1) as usize)?;
+
+    let mut res: Vec<ValidatorMetrics> = vec![];
+    for validator in validators.iter() {
+        // note: this change will help with missing validator / timestamps due to deletion.
         // let fetch_metric_result = METRICS_HISTORY.load(
         //     deps.storage,
         //     (&validator_operator_addr[start as usize], U64Key::new(timestamp)));
@@ -967,16 +2993,60 @@ fn query_validators_metrics_by_timestamp(
         // }
         res.push(METRICS_HISTORY.load(
             deps.storage,
-            (
-                &validator_operator_addr[start as usize],
-                U64Key::new(timestamp),
-            ),
+            (&validator.operator_address, U64Key::new(timestamp)),
         )?);
-        start += 1;
     }
     Ok(res)
 }
 
+// Validators at positions [start, start + count) when walking the registry in insertion order
+fn get_validators_by_position(
+    storage: &dyn Storage,
+    start: usize,
+    count: usize,
+) -> StdResult<Vec<ValidatorAccounts>> {
+    validators()
+        .idx
+        .insertion_order
+        .range(storage, None, None, Order::Ascending)
+        .skip(start)
+        .take(count)
+        .map(|item| item.map(|(_, validator)| validator))
+        .collect()
+}
+
+fn query_validators(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ValidatorAccounts>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|order| Bound::Exclusive(U64Key::new(order).into()));
+
+    validators()
+        .idx
+        .insertion_order
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, validator)| validator))
+        .collect()
+}
+
+fn query_validators_by_account(
+    deps: Deps,
+    account_addr: String,
+) -> StdResult<Vec<ValidatorAccounts>> {
+    let account_addr = deps.api.addr_validate(&account_addr)?;
+
+    validators()
+        .idx
+        .account
+        .prefix(account_addr.as_bytes().to_vec())
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, validator)| validator))
+        .collect()
+}
+
 // off chain code
 
 fn save_off_chain_details(
@@ -985,7 +3055,7 @@ fn save_off_chain_details(
     timestamp: u64,
     details: OffchainTimestampMetaData,
 ) -> Result<Response, ContractError> {
-    if !sender_is_manager(&deps, &info) {
+    if !sender_has_role(deps.storage, &info.sender, Role::OffChainPublisher)? {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -1009,7 +3079,7 @@ fn add_off_chain_validator(
     info: MessageInfo,
     validator_addr: Addr,
 ) -> Result<Response, ContractError> {
-    if !sender_is_manager(&deps, &info) {
+    if !sender_has_role(deps.storage, &info.sender, Role::OffChainPublisher)? {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -1045,7 +3115,7 @@ fn remove_off_chain_metrics_for_timestamp(
     let mut timestamp_removed = false;
     let mut validators_removed = 0;
 
-    if !sender_is_manager(&deps, &info) {
+    if !sender_has_role(deps.storage, &info.sender, Role::Pruner)? {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -1066,7 +3136,7 @@ fn remove_off_chain_metrics_for_timestamp(
                 .remove(deps.storage, (U64Key::from(timestamp), U16Key::from(idx)))
         });
 
-        if (validators_removed as u16) < (no_of_validators_to_remove as u16) {
+        if (validators_removed as u16) < no_of_validators_to_remove {
             timestamp_removed = true;
             OFF_CHAIN_TIMESTAMPS.remove(deps.storage, U64Key::from(timestamp));
             OFF_CHAIN_TIMESTAMP_META_DATA.remove(deps.storage, U64Key::from(timestamp));
@@ -1097,7 +3167,7 @@ fn add_off_chain_validator_metrics(
     timestamp: u64,
     metrics_to_be_added: Vec<OffChainValidatorMetrics>,
 ) -> Result<Response, ContractError> {
-    if !sender_is_manager(&deps, &info) {
+    if !sender_has_role(deps.storage, &info.sender, Role::OffChainPublisher)? {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -1135,7 +3205,6 @@ fn off_chain_metrics_exists(deps: &DepsMut, timestamp: u64, validator_idx: u16)
 fn get_off_chain_metrics_timestamps(deps: Deps) -> StdResult<OffChainTimestamps> {
     let off_chain_timestamps: Vec<u64> = OFF_CHAIN_TIMESTAMPS
         .keys(deps.storage, Option::None, Option::None, Order::Ascending)
-        .into_iter()
         .map(conversion_utils::u64_from_vec_u8)
         .collect();
 
@@ -1185,7 +3254,6 @@ fn get_off_chain_state(deps: Deps) -> StdResult<OffChainState> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::constants::OFF_CHAIN_METRICS_FOR_VALIDATOR;
     use crate::state::ConversionRatio;
     use cosmwasm_std::testing::{
         mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
@@ -1228,221 +3296,2042 @@ mod tests {
             vault_denom: TEST_DENOM.to_string(),
             batch_size: 10,
         };
-        let info = mock_info("creator", &coins(2, TEST_DENOM));
-        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &coins(2, TEST_DENOM));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn test_record_metrics() {
+        let mut deps = initiate_test_validators_and_metrics();
+        let info = get_test_msg_info();
+        let env = mock_env();
+        let timestamp = 10;
+
+        let _res = record_validator_metrics(deps.as_mut(), env, info, timestamp);
+    }
+
+    #[test]
+    fn test_get_all_validator_metrics() {
+        let deps = mock_dependencies(&[]);
+        let validator = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
+        let _res = query_all_validator_metrics(deps.as_ref(), validator);
+    }
+
+    #[test]
+    fn test_add_validator() {
+        let mut deps = mock_dependencies(&[]);
+        let info = mock_info("creator", &[]);
+        let validator_opr = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
+        let validator_acc = Addr::unchecked(TEST_VALIDATOR_ACC_ADDR).to_string();
+        let _res = add_validator(deps.as_mut(), info, validator_opr, validator_acc);
+    }
+
+    #[test]
+    fn test_add_validator_validates_funds() {
+        let mut dependencies = instantiate_test_contract();
+        set_test_staking(&mut dependencies, &mock_env(), 0);
+        let add = |dependencies: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                   funds: &[Coin]| {
+            add_validator(
+                dependencies.as_mut(),
+                mock_info(TEST_OWNER_ADDR, funds),
+                Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
+                TEST_VALIDATOR_ACC_ADDR.to_string(),
+            )
+        };
+
+        let err = add(&mut dependencies, &[]).unwrap_err();
+        assert!(matches!(err, ContractError::NoFundsFound {}));
+        let err = add(&mut dependencies, &[Coin::new(10, "uusd")]).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFundsDenom { .. }));
+        let err = add(
+            &mut dependencies,
+            &[Coin::new(10, TEST_DENOM), Coin::new(10, "uusd")],
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::MultipleCoinsSent {}));
+        let err = add(&mut dependencies, &[Coin::new(9, TEST_DENOM)]).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientFunds {}));
+
+        let res = add(&mut dependencies, &[Coin::new(15, TEST_DENOM)]).unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: TEST_OWNER_ADDR.to_string(),
+                amount: coins(5, TEST_DENOM),
+            })
+        );
+    }
+
+    #[test]
+    fn test_delete_metrics_for_validator() {
+        //initiate state
+        let mut dependencies = initiate_test_validators_and_metrics();
+
+        let state = STATE.load(&dependencies.storage).unwrap();
+        let metrics = METRICS_HISTORY.load(
+            &dependencies.storage,
+            (
+                &Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
+                U64Key::from(TEST_TIMESTAMP_1),
+            ),
+        );
+
+        // check if state has the timestamp
+        assert_eq!(2, state.cron_timestamps.len());
+        // check if metrics response is giving data
+        assert!(metrics.is_ok());
+        // check if remove validator removes both validator and metrics
+        let result = delete_metrics_for_validator(
+            dependencies.as_mut(),
+            get_test_msg_info(),
+            Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
+            0,
+            1,
+        );
+
+        let timestamps_left = result
+            .unwrap()
+            .attributes
+            .into_iter()
+            .find(|item| item.key.eq("timestamps_left"))
+            .unwrap()
+            .value;
+
+        assert_eq!(timestamps_left, "1");
+    }
+
+    //tests if the metrics and timestamp exists, and upon deletion of timestamp both go away
+    #[test]
+    fn test_delete_metrics_for_timestamp() {
+        //initiate state
+        let mut dependencies = initiate_test_validators_and_metrics();
+
+        let state = STATE.load(&dependencies.storage).unwrap();
+        let metrics = METRICS_HISTORY.load(
+            &dependencies.storage,
+            (
+                &Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
+                U64Key::from(TEST_TIMESTAMP_1),
+            ),
+        );
+
+        // check if state has the timestamp
+        assert_eq!(2, state.cron_timestamps.len());
+        // check if metrics response is giving data
+        assert!(metrics.is_ok());
+
+        // delete one validator from this timestamp metrics
+        let result = delete_metrics_for_timestamp(
+            dependencies.as_mut(),
+            get_test_msg_info(),
+            TEST_TIMESTAMP_1,
+            0,
+            1,
+        );
+
+        // check if result is okay
+        assert!(result.as_ref().ok().is_some());
+        let validators_left = result
+            .unwrap()
+            .attributes
+            .into_iter()
+            .find(|attribute| attribute.key.eq("validators_left"))
+            .unwrap()
+            .value;
+
+        // assert that one validator is still left for the timestamp
+        assert_eq!(validators_left, "1");
+
+        // delete second validator from this timestamp metrics
+        let result = delete_metrics_for_timestamp(
+            dependencies.as_mut(),
+            get_test_msg_info(),
+            TEST_TIMESTAMP_1,
+            1,
+            1,
+        );
+
+        // check if result is okay
+        assert!(result.as_ref().ok().is_some());
+        let validators_left = result
+            .unwrap()
+            .attributes
+            .into_iter()
+            .find(|attribute| attribute.key.eq("validators_left"))
+            .unwrap()
+            .value;
+
+        // assert that all validators are deleted for this timestamp
+        assert_eq!(validators_left, "0");
+
+        let metrics = METRICS_HISTORY.load(
+            &dependencies.storage,
+            (
+                &Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
+                U64Key::from(TEST_TIMESTAMP_1),
+            ),
+        );
+
+        // check if metrics response is giving error, due to this metric not being available
+        assert!(metrics.is_err());
+    }
+
+    #[test]
+    fn test_delete_timestamp() {
+        let mut dependencies = initiate_test_validators_and_metrics();
+        delete_metrics_for_timestamp(
+            dependencies.as_mut(),
+            get_test_msg_info(),
+            TEST_TIMESTAMP_1,
+            0,
+            2,
+        )
+        .unwrap();
+
+        let result = remove_timestamp(dependencies.as_mut(), get_test_msg_info(), TEST_TIMESTAMP_1);
+        assert!(result.is_ok());
+    }
+
+    fn initiate_test_validators_and_metrics() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut dependencies = instantiate_test_contract();
+
+        // initiate state to have two timestamp metrics for two validators
+        let _msg = get_test_msg_info();
+
+        // initiate state
+        let test_validators = get_test_validators();
+        for validator in test_validators.iter() {
+            validators()
+                .save(
+                    dependencies.as_mut().storage,
+                    &validator.operator_address,
+                    validator,
+                )
+                .unwrap();
+        }
+        let _ = STATE.update(dependencies.as_mut().storage, |mut s| -> StdResult<_> {
+            s.next_validator_insertion_order = test_validators.len() as u64;
+            s.total_validators = test_validators.len() as u64;
+            s.cron_timestamps = vec![TEST_TIMESTAMP_1, TEST_TIMESTAMP_2];
+            Ok(s)
+        });
+
+        // initiate metrics
+        let _ = METRICS_HISTORY.save(
+            dependencies.as_mut().storage,
+            (
+                &Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
+                U64Key::from(TEST_TIMESTAMP_1),
+            ),
+            &get_test_metrics(TEST_VALIDATOR_OPR_ADDR, TEST_TIMESTAMP_1),
+        );
+        dependencies
+    }
+
+    // Only the first validator is left, with a delegation of 100 recorded at TEST_TIMESTAMP_2 and
+    // the staking of `set_test_staking` on chain.
+    fn initiate_test_validator_with_previous_metrics(
+        delegated_amount: u128,
+        update_previous: impl FnOnce(&mut ValidatorMetrics),
+    ) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut dependencies = initiate_test_validators_and_metrics();
+        validators()
+            .remove(
+                dependencies.as_mut().storage,
+                &Addr::unchecked(TEST_VALIDATOR_OPR_ADDR_2),
+            )
+            .unwrap();
+
+        let mut previous = get_test_metrics(TEST_VALIDATOR_OPR_ADDR, TEST_TIMESTAMP_2);
+        previous.delegated_amount = Uint128::new(100);
+        previous.slashing_pointer = Decimal::one();
+        previous.commission = Decimal::percent(5);
+        update_previous(&mut previous);
+        METRICS_HISTORY
+            .save(
+                dependencies.as_mut().storage,
+                (
+                    &Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
+                    U64Key::new(TEST_TIMESTAMP_2),
+                ),
+                &previous,
+            )
+            .unwrap();
+        set_test_staking(&mut dependencies, &mock_env(), delegated_amount);
+        dependencies
+    }
+
+    fn get_test_msg_info() -> MessageInfo {
+        MessageInfo {
+            sender: Addr::unchecked(TEST_OWNER_ADDR),
+            funds: vec![Coin::new(1000, TEST_DENOM)],
+        }
+    }
+
+    fn get_test_metrics(validator_addr: &str, timestamp: u64) -> ValidatorMetrics {
+        ValidatorMetrics {
+            operator_addr: Addr::unchecked(validator_addr),
+            rewards: Default::default(),
+            delegated_amount: Default::default(),
+            self_delegated_amount: Default::default(),
+            slashing_pointer: Default::default(),
+            commission: Default::default(),
+            max_commission: Default::default(),
+            timestamp,
+            rewards_in_coins: vec![],
+            voting_power: Default::default(),
+            network_share: Default::default(),
+            block_height: Default::default(),
+            block_time: Default::default(),
+            rewards_reset: false,
+        }
+    }
+
+    fn get_test_validators() -> Vec<ValidatorAccounts> {
+        vec![
+            ValidatorAccounts {
+                operator_address: Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
+                account_address: Addr::unchecked(TEST_VALIDATOR_ACC_ADDR),
+                insertion_order: 0,
+                staked_amount: Uint128::new(10),
+            },
+            ValidatorAccounts {
+                operator_address: Addr::unchecked(TEST_VALIDATOR_OPR_ADDR_2),
+                account_address: Addr::unchecked(TEST_VALIDATOR_ACC_ADDR_2),
+                insertion_order: 1,
+                staked_amount: Uint128::new(10),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_get_validators_to_record_in_batches() {
+        let mut dependencies = initiate_test_validators_and_metrics();
+        CONFIG
+            .update(dependencies.as_mut().storage, |mut c| -> StdResult<_> {
+                c.batch_size = 1;
+                Ok(c)
+            })
+            .unwrap();
+
+        let timestamp = TEST_TIMESTAMP_2 + 1;
+        let first = get_validators_to_record(dependencies.as_mut().storage, timestamp).unwrap();
+        assert_eq!(first, vec![get_test_validators()[0].clone()]);
+
+        // removing an already recorded validator mid cron should not skip the next one
+        validators()
+            .remove(
+                dependencies.as_mut().storage,
+                &Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
+            )
+            .unwrap();
+
+        let second = get_validators_to_record(dependencies.as_mut().storage, timestamp).unwrap();
+        assert_eq!(second, vec![get_test_validators()[1].clone()]);
+
+        let third = get_validators_to_record(dependencies.as_mut().storage, timestamp).unwrap();
+        assert!(third.is_empty());
+    }
+
+    #[test]
+    fn test_query_validators_by_account() {
+        let dependencies = initiate_test_validators_and_metrics();
+
+        let page = query_validators(dependencies.as_ref(), Some(0), None).unwrap();
+        assert_eq!(page, vec![get_test_validators()[1].clone()]);
+
+        let by_account =
+            query_validators_by_account(dependencies.as_ref(), TEST_VALIDATOR_ACC_ADDR.to_string())
+                .unwrap();
+        assert_eq!(by_account, vec![get_test_validators()[0].clone()]);
+    }
+
+    #[test]
+    fn test_migrate_validators_to_registry() {
+        let mut dependencies = instantiate_test_contract();
+        // state as stored before the validator registry, validators live inside the state
+        let legacy_state = format!(
+            r#"{{"vault_denom":"{}","validators":[{{"operator_address":"{}","account_address":"{}"}},{{"operator_address":"{}","account_address":"{}"}}],"cron_timestamps":[],"validator_index_for_next_cron":0}}"#,
+            TEST_DENOM,
+            TEST_VALIDATOR_OPR_ADDR,
+            TEST_VALIDATOR_ACC_ADDR,
+            TEST_VALIDATOR_OPR_ADDR_2,
+            TEST_VALIDATOR_ACC_ADDR_2
+        );
+        dependencies.storage.set(b"state", legacy_state.as_bytes());
+
+        let res = migrate(
+            dependencies.as_mut(),
+            mock_env(),
+            MigrateMsg {
+                manager_address: Addr::unchecked(TEST_OWNER_ADDR),
+            },
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key.eq("validators_migrated") && a.value.eq("2")));
+
+        let state = STATE.load(&dependencies.storage).unwrap();
+        assert_eq!(state.total_validators, 2);
+        assert_eq!(state.next_validator_insertion_order, 2);
+        assert!(LEGACY_STATE
+            .load(&dependencies.storage)
+            .unwrap()
+            .validators
+            .is_empty());
+        assert_eq!(
+            query_validators(dependencies.as_ref(), None, None).unwrap(),
+            get_test_validators()
+        );
+    }
+
+    #[test]
+    fn test_grant_and_revoke_role() {
+        let mut dependencies = initiate_test_validators_and_metrics();
+        let bot = mock_info("cronbot", &[]);
+
+        // only admins can grant roles
+        let res = grant_role(
+            dependencies.as_mut(),
+            bot.clone(),
+            Role::MetricsRecorder,
+            "cronbot".to_string(),
+        );
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+
+        grant_role(
+            dependencies.as_mut(),
+            get_test_msg_info(),
+            Role::MetricsRecorder,
+            "cronbot".to_string(),
+        )
+        .unwrap();
+
+        let storage = &dependencies.storage;
+        assert!(sender_has_role(storage, &bot.sender, Role::MetricsRecorder).unwrap());
+        assert!(!sender_has_role(storage, &bot.sender, Role::Admin).unwrap());
+        assert!(!sender_has_role(storage, &bot.sender, Role::Pruner).unwrap());
+
+        let res = remove_validator(
+            dependencies.as_mut(),
+            mock_env(),
+            bot.clone(),
+            Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
+        );
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+
+        assert_eq!(
+            query_roles(dependencies.as_ref(), Some(bot.sender.clone())).unwrap(),
+            vec![RoleGrant {
+                role: Role::MetricsRecorder,
+                addr: bot.sender.clone(),
+            }]
+        );
+
+        revoke_role(
+            dependencies.as_mut(),
+            get_test_msg_info(),
+            Role::MetricsRecorder,
+            "cronbot".to_string(),
+        )
+        .unwrap();
+        assert!(
+            !sender_has_role(&dependencies.storage, &bot.sender, Role::MetricsRecorder).unwrap()
+        );
+        assert!(query_roles(dependencies.as_ref(), None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_manager_transfer() {
+        let mut dependencies = instantiate_test_contract();
+        let mut env = mock_env();
+        let new_manager = mock_info("newmanager", &[]);
+
+        let res = accept_managership(dependencies.as_mut(), env.clone(), new_manager.clone());
+        assert!(matches!(res, Err(ContractError::NoPendingManager {})));
+
+        propose_new_manager(
+            dependencies.as_mut(),
+            env.clone(),
+            get_test_msg_info(),
+            "newmanager".to_string(),
+            Some(100),
+        )
+        .unwrap();
+
+        // only the proposed address can accept
+        let res = accept_managership(dependencies.as_mut(), env.clone(), mock_info("other", &[]));
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+
+        env.block.time = env.block.time.plus_seconds(101);
+        let res = accept_managership(dependencies.as_mut(), env.clone(), new_manager.clone());
+        assert!(matches!(res, Err(ContractError::ManagerProposalExpired {})));
+
+        propose_new_manager(
+            dependencies.as_mut(),
+            env.clone(),
+            get_test_msg_info(),
+            "newmanager".to_string(),
+            Some(u64::MAX),
+        )
+        .unwrap();
+        let pending_manager = CONFIG
+            .load(&dependencies.storage)
+            .unwrap()
+            .pending_manager
+            .unwrap();
+        assert_eq!(pending_manager.expires_at, Some(u64::MAX));
+
+        propose_new_manager(
+            dependencies.as_mut(),
+            env.clone(),
+            get_test_msg_info(),
+            "newmanager".to_string(),
+            None,
+        )
+        .unwrap();
+        accept_managership(dependencies.as_mut(), env, new_manager.clone()).unwrap();
+
+        let config = CONFIG.load(&dependencies.storage).unwrap();
+        assert_eq!(config.manager, new_manager.sender);
+        assert_eq!(config.pending_manager, None);
+
+        let res = cancel_manager_proposal(dependencies.as_mut(), get_test_msg_info());
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+    }
+
+    #[test]
+    fn test_query_apr_windows() {
+        let mut dependencies = instantiate_test_contract();
+        let validator = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
+        let day = 86400;
+        for (timestamp, rewards) in [(0, 0), (day, 1), (2 * day, 2), (10 * day, 10)] {
+            let mut metric = get_test_metrics(TEST_VALIDATOR_OPR_ADDR, timestamp);
+            metric.rewards = Decimal::from_ratio(rewards as u128, 1_u128);
+            metric.delegated_amount = Uint128::new(100);
+            METRICS_HISTORY
+                .save(
+                    dependencies.as_mut().storage,
+                    (&validator, U64Key::new(timestamp)),
+                    &metric,
+                )
+                .unwrap();
+        }
+
+        STATE
+            .update(dependencies.as_mut().storage, |mut s| -> StdResult<_> {
+                s.cron_timestamps = vec![0, day, 2 * day, 10 * day];
+                Ok(s)
+            })
+            .unwrap();
+
+        let res = query_apr_windows(
+            dependencies.as_ref(),
+            validator.clone(),
+            vec![7 * day, 0, 1],
+        )
+        .unwrap();
+        assert_eq!(res.latest_timestamp, 10 * day);
+
+        // 7 days back is day 3, the nearest record is day 2
+        assert_eq!(res.windows[0].timestamp1, 2 * day);
+        assert!(res.windows[0].apr.is_some());
+        // since inception
+        assert_eq!(res.windows[1].timestamp1, 0);
+        assert_eq!(
+            res.windows[1].apr.as_ref().unwrap().apr,
+            Decimal::from_ratio(365_u128, 1_u128)
+        );
+        // nothing recorded before the latest cron within this window
+        assert_eq!(res.windows[2].timestamp1, 10 * day);
+        assert!(res.windows[2].apr.is_none());
+
+        // the validator missed the latest cron, windows stay anchored on the cron
+        STATE
+            .update(dependencies.as_mut().storage, |mut s| -> StdResult<_> {
+                s.cron_timestamps.push(20 * day);
+                Ok(s)
+            })
+            .unwrap();
+        let res =
+            query_apr_windows(dependencies.as_ref(), validator.clone(), vec![7 * day]).unwrap();
+        assert_eq!(res.latest_timestamp, 20 * day);
+        // 7 days back is day 13, the nearest record is day 10
+        assert_eq!(res.windows[0].timestamp1, 10 * day);
+        assert_eq!(res.windows[0].timestamp2, 10 * day);
+        assert!(res.windows[0].apr.is_none());
+
+        // no apr against a zero delegation
+        let mut metric = get_test_metrics(TEST_VALIDATOR_OPR_ADDR, 10 * day);
+        metric.rewards = Decimal::from_ratio(10_u128, 1_u128);
+        METRICS_HISTORY
+            .save(
+                dependencies.as_mut().storage,
+                (&validator, U64Key::new(10 * day)),
+                &metric,
+            )
+            .unwrap();
+        let mut metric = get_test_metrics(TEST_VALIDATOR_OPR_ADDR, 20 * day);
+        metric.delegated_amount = Uint128::new(100);
+        METRICS_HISTORY
+            .save(
+                dependencies.as_mut().storage,
+                (&validator, U64Key::new(20 * day)),
+                &metric,
+            )
+            .unwrap();
+        let res = query_apr_windows(dependencies.as_ref(), validator, vec![7 * day]).unwrap();
+        assert_eq!(res.windows[0].timestamp1, 10 * day);
+        assert_eq!(res.windows[0].timestamp2, 20 * day);
+        assert!(res.windows[0].apr.is_none());
+    }
+
+    #[test]
+    fn test_resolve_metrics() {
+        let mut dependencies = initiate_test_validators_and_metrics();
+        let validator = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
+        METRICS_HISTORY
+            .save(
+                dependencies.as_mut().storage,
+                (&validator, U64Key::from(TEST_TIMESTAMP_2)),
+                &get_test_metrics(TEST_VALIDATOR_OPR_ADDR, TEST_TIMESTAMP_2),
+            )
+            .unwrap();
+
+        let resolve = |timestamp: u64, resolution: TimestampResolution| {
+            query_validator_metrics_by_timestamp(
+                dependencies.as_ref(),
+                validator.clone(),
+                timestamp,
+                resolution,
+            )
+            .map(|metric| metric.timestamp)
+        };
+        let near_1 = TEST_TIMESTAMP_1 + 10;
+        assert!(resolve(near_1, TimestampResolution::Exact).is_err());
+        assert_eq!(
+            resolve(near_1, TimestampResolution::Floor),
+            Ok(TEST_TIMESTAMP_1)
+        );
+        assert_eq!(
+            resolve(near_1, TimestampResolution::Ceil),
+            Ok(TEST_TIMESTAMP_2)
+        );
+        assert_eq!(
+            resolve(near_1, TimestampResolution::Nearest),
+            Ok(TEST_TIMESTAMP_1)
+        );
+        assert!(resolve(TEST_TIMESTAMP_1 - 1, TimestampResolution::Floor).is_err());
+        assert!(resolve(TEST_TIMESTAMP_2 + 1, TimestampResolution::Ceil).is_err());
+
+        // both ends resolving to the same record can't make an interval
+        let interval = resolve_metrics_interval(
+            &dependencies.storage,
+            &validator,
+            TEST_TIMESTAMP_1,
+            TEST_TIMESTAMP_1 + 10,
+            &TimestampResolution::Nearest,
+        )
+        .unwrap();
+        assert!(interval.is_none());
+    }
+
+    #[test]
+    fn test_query_validator_scores() {
+        let mut dependencies = initiate_test_validators_and_metrics();
+        for (validator, rewards) in [(TEST_VALIDATOR_OPR_ADDR, 1), (TEST_VALIDATOR_OPR_ADDR_2, 2)] {
+            for (timestamp, multiplier) in [(TEST_TIMESTAMP_1, 0), (TEST_TIMESTAMP_2, 1)] {
+                let mut metric = get_test_metrics(validator, timestamp);
+                metric.rewards = Decimal::from_ratio((rewards * multiplier) as u128, 1_u128);
+                metric.delegated_amount = Uint128::new(10);
+                metric.slashing_pointer = Decimal::one();
+                METRICS_HISTORY
+                    .save(
+                        dependencies.as_mut().storage,
+                        (&Addr::unchecked(validator), U64Key::new(timestamp)),
+                        &metric,
+                    )
+                    .unwrap();
+            }
+        }
+
+        let scores = query_validator_scores(
+            dependencies.as_ref(),
+            TEST_TIMESTAMP_1,
+            TEST_TIMESTAMP_2,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[0].addr, Addr::unchecked(TEST_VALIDATOR_OPR_ADDR_2));
+
+        let next_page = query_validator_scores(
+            dependencies.as_ref(),
+            TEST_TIMESTAMP_1,
+            TEST_TIMESTAMP_2,
+            Some(scores[0].addr.clone()),
+            Some(1),
+        )
+        .unwrap();
+        assert_eq!(next_page, vec![scores[1].clone()]);
+
+        let res = update_score_weights(
+            dependencies.as_mut(),
+            get_test_msg_info(),
+            ScoreWeights {
+                apr: Decimal::zero(),
+                commission: Decimal::zero(),
+                max_commission: Decimal::zero(),
+                self_delegation: Decimal::zero(),
+                slashing: Decimal::zero(),
+                recording: Decimal::zero(),
+            },
+        );
+        assert!(matches!(res, Err(ContractError::InvalidScoreWeights {})));
+    }
+
+    #[test]
+    fn test_record_metrics_detects_slashing() {
+        // our delegation went from 100 to 95
+        let mut dependencies = initiate_test_validator_with_previous_metrics(95, |_| {});
+        let env = mock_env();
+        let validator = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
+
+        let timestamp = TEST_TIMESTAMP_2 + 1;
+        let res =
+            record_validator_metrics(dependencies.as_mut(), env, get_test_msg_info(), timestamp)
+                .unwrap();
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "slashing");
+
+        let expected = SlashingEvent {
+            validator: validator.clone(),
+            timestamp,
+            ratio: Decimal::percent(95),
+            amount_lost: Uint128::new(5),
+        };
+        assert_eq!(
+            query_slashing_events(dependencies.as_ref(), Some(validator), None, None).unwrap(),
+            vec![expected.clone()]
+        );
+        assert_eq!(
+            query_slashing_events(dependencies.as_ref(), None, None, None).unwrap(),
+            vec![expected]
+        );
+        assert!(
+            query_slashing_events(dependencies.as_ref(), None, Some(timestamp), None)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            query_slashing_events(dependencies.as_ref(), None, Some(u64::MAX), None)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_record_metrics_tracks_commission_changes() {
+        // commission goes from 2% to 5%
+        let mut dependencies = initiate_test_validator_with_previous_metrics(100, |previous| {
+            previous.commission = Decimal::percent(2);
+        });
+        let env = mock_env();
+        let validator = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
+
+        let timestamp = TEST_TIMESTAMP_2 + 1;
+        let res =
+            record_validator_metrics(dependencies.as_mut(), env, get_test_msg_info(), timestamp)
+                .unwrap();
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "commission_change");
+
+        let changes =
+            query_commission_changes(dependencies.as_ref(), Some(validator.clone()), None, None)
+                .unwrap();
+        assert_eq!(
+            changes,
+            vec![CommissionChangeResponse {
+                validator,
+                timestamp,
+                previous_commission: Decimal::percent(2),
+                new_commission: Decimal::percent(5),
+                above_threshold: true,
+            }]
+        );
+
+        update_config(
+            dependencies.as_mut(),
+            get_test_msg_info(),
+            None,
+            Some(Decimal::percent(5)),
+            None,
+            None,
+        )
+        .unwrap();
+        let changes =
+            query_commission_changes(dependencies.as_ref(), None, Some(timestamp), None).unwrap();
+        assert!(!changes[0].above_threshold);
+        assert!(
+            query_commission_changes(dependencies.as_ref(), None, Some(timestamp + 1), None)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_record_voting_power() {
+        let mut dependencies = initiate_test_validators_and_metrics();
+        let validator = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
+        let voting_powers = vec![ValidatorVotingPower {
+            addr: validator.clone(),
+            tokens: Uint128::new(250),
+        }];
+
+        let err = record_voting_power(
+            dependencies.as_mut(),
+            mock_info("other", &[]),
+            TEST_TIMESTAMP_1,
+            Uint128::new(1000),
+            voting_powers.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let err = record_voting_power(
+            dependencies.as_mut(),
+            get_test_msg_info(),
+            TEST_TIMESTAMP_1,
+            Uint128::new(400),
+            vec![
+                voting_powers[0].clone(),
+                ValidatorVotingPower {
+                    addr: Addr::unchecked(TEST_VALIDATOR_OPR_ADDR_2),
+                    tokens: Uint128::new(200),
+                },
+            ],
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::VotingPowerAboveTotalBonded {}));
+
+        // no metrics recorded for this timestamp, the validator is skipped
+        let res = record_voting_power(
+            dependencies.as_mut(),
+            get_test_msg_info(),
+            TEST_TIMESTAMP_2,
+            Uint128::new(1000),
+            voting_powers.clone(),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key.eq("validators_recorded") && a.value.eq("0")));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key.eq("validators_skipped") && a.value.eq("1")));
+
+        let history = query_voting_power_history(
+            dependencies.as_ref(),
+            validator.clone(),
+            TEST_TIMESTAMP_1,
+            TEST_TIMESTAMP_1,
+        )
+        .unwrap();
+        assert_eq!(history[0].voting_power, None);
+        assert_eq!(history[0].network_share, None);
+
+        record_voting_power(
+            dependencies.as_mut(),
+            get_test_msg_info(),
+            TEST_TIMESTAMP_1,
+            Uint128::new(1000),
+            voting_powers,
+        )
+        .unwrap();
+
+        let history = query_voting_power_history(
+            dependencies.as_ref(),
+            validator,
+            TEST_TIMESTAMP_1,
+            TEST_TIMESTAMP_2,
+        )
+        .unwrap();
+        assert_eq!(
+            history,
+            vec![VotingPowerRecord {
+                timestamp: TEST_TIMESTAMP_1,
+                voting_power: Some(Uint128::new(250)),
+                network_share: Some(Decimal::percent(25)),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_record_metrics_updates_timestamp_aggregate() {
+        let mut dependencies = initiate_test_validator_with_previous_metrics(100, |_| {});
+        let env = mock_env();
+
+        let timestamp = TEST_TIMESTAMP_2 + 31_536_000;
+        record_validator_metrics(dependencies.as_mut(), env, get_test_msg_info(), timestamp)
+            .unwrap();
+
+        let aggregate = query_timestamp_aggregate(dependencies.as_ref(), timestamp).unwrap();
+        assert_eq!(aggregate.validators_recorded, 1);
+        assert_eq!(aggregate.validators_with_apr, 1);
+        // rewards of 1 over a year on a delegation of 100, in percent
+        assert_eq!(aggregate.median_apr, Some(Decimal::one()));
+        assert_eq!(aggregate.mean_apr, aggregate.median_apr);
+        assert_eq!(aggregate.total_delegated, Uint128::new(100));
+        assert_eq!(aggregate.stake_weighted_commission, Decimal::percent(5));
+
+        assert!(query_timestamp_aggregate(dependencies.as_ref(), TEST_TIMESTAMP_1).is_err());
+    }
+
+    #[test]
+    fn test_delete_metrics_invalidates_timestamp_aggregate() {
+        let mut dependencies = initiate_test_validator_with_previous_metrics(100, |_| {});
+        let env = mock_env();
+
+        let timestamp = TEST_TIMESTAMP_2 + 31_536_000;
+        record_validator_metrics(dependencies.as_mut(), env, get_test_msg_info(), timestamp)
+            .unwrap();
+        assert!(query_timestamp_aggregate(dependencies.as_ref(), timestamp).is_ok());
+
+        delete_metrics_for_timestamp(dependencies.as_mut(), get_test_msg_info(), timestamp, 0, 1)
+            .unwrap();
+        assert!(query_timestamp_aggregate(dependencies.as_ref(), timestamp).is_err());
+    }
+
+    #[test]
+    fn test_cron_status() {
+        let mut dependencies = initiate_test_validators_and_metrics();
+        let mut env = mock_env();
+        CONFIG
+            .update(dependencies.as_mut().storage, |mut c| -> StdResult<_> {
+                c.batch_size = 1;
+                Ok(c)
+            })
+            .unwrap();
+        METRICS_HISTORY.remove(
+            dependencies.as_mut().storage,
+            (
+                &Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
+                U64Key::new(TEST_TIMESTAMP_1),
+            ),
+        );
+        // the second validator is out of the active set but still holds our delegation
+        set_test_staking_entries(
+            &mut dependencies,
+            &[TEST_VALIDATOR_OPR_ADDR],
+            &[
+                test_delegation(&env.contract.address, TEST_VALIDATOR_OPR_ADDR, 100, 0),
+                test_delegation(&env.contract.address, TEST_VALIDATOR_OPR_ADDR_2, 100, 0),
+                test_delegation(
+                    &Addr::unchecked(TEST_VALIDATOR_ACC_ADDR),
+                    TEST_VALIDATOR_OPR_ADDR,
+                    100,
+                    0,
+                ),
+            ],
+        );
+
+        let timestamp = TEST_TIMESTAMP_2 + 1;
+        let started_at_height = env.block.height;
+        record_validator_metrics(
+            dependencies.as_mut(),
+            env.clone(),
+            get_test_msg_info(),
+            timestamp,
+        )
+        .unwrap();
+
+        let status = query_cron_status(dependencies.as_ref(), timestamp).unwrap();
+        assert_eq!(status.total_validators, 2);
+        assert_eq!(status.recorded, 1);
+        assert!(!status.is_complete);
+
+        env.block.height += 1;
+        record_validator_metrics(
+            dependencies.as_mut(),
+            env.clone(),
+            get_test_msg_info(),
+            timestamp,
+        )
+        .unwrap();
+
+        let status = query_cron_status(dependencies.as_ref(), timestamp).unwrap();
+        assert_eq!(
+            status,
+            CronStatusResponse {
+                timestamp,
+                total_validators: 2,
+                recorded: 1,
+                skipped: vec![SkippedValidator {
+                    addr: Addr::unchecked(TEST_VALIDATOR_OPR_ADDR_2),
+                    reason: ValidatorStatus::MissingFromSet,
+                }],
+                started_at_height,
+                started_at_time: env.block.time.seconds(),
+                finished_at_height: Some(env.block.height),
+                finished_at_time: Some(env.block.time.seconds()),
+                is_complete: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_resolve_cron_timestamp() {
+        let mut dependencies = instantiate_test_contract();
+        let env = mock_env();
+        let block_time = env.block.time.seconds();
+
+        assert_eq!(
+            resolve_cron_timestamp(&dependencies.storage, &env, Some(TEST_TIMESTAMP_1)).unwrap(),
+            TEST_TIMESTAMP_1
+        );
+        assert!(matches!(
+            resolve_cron_timestamp(&dependencies.storage, &env, None).unwrap_err(),
+            ContractError::TimestampRequired {}
+        ));
+
+        let schedule = CronSchedule {
+            interval: 86400,
+            tolerance: 300,
+        };
+        assert!(update_cron_schedule(
+            dependencies.as_mut(),
+            mock_info("other", &[]),
+            Some(schedule.clone())
+        )
+        .is_err());
+        assert!(matches!(
+            update_cron_schedule(
+                dependencies.as_mut(),
+                get_test_msg_info(),
+                Some(CronSchedule {
+                    interval: 0,
+                    tolerance: 300
+                })
+            )
+            .unwrap_err(),
+            ContractError::InvalidCronSchedule {}
+        ));
+        update_cron_schedule(dependencies.as_mut(), get_test_msg_info(), Some(schedule)).unwrap();
+
+        let midnight = block_time - block_time % 86400;
+        assert_eq!(
+            resolve_cron_timestamp(&dependencies.storage, &env, None).unwrap(),
+            midnight
+        );
+        assert_eq!(
+            resolve_cron_timestamp(&dependencies.storage, &env, Some(block_time - 300)).unwrap(),
+            midnight
+        );
+        assert!(matches!(
+            resolve_cron_timestamp(&dependencies.storage, &env, Some(block_time + 301))
+                .unwrap_err(),
+            ContractError::TimestampOutsideTolerance {}
+        ));
+    }
+
+    #[test]
+    fn test_query_validator_metrics_by_height() {
+        let mut dependencies = initiate_test_validators_and_metrics();
+        let validator = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
+        for (timestamp, height) in [(TEST_TIMESTAMP_2, 100), (TEST_TIMESTAMP_2 + 1, 200)] {
+            let mut metrics = get_test_metrics(TEST_VALIDATOR_OPR_ADDR, timestamp);
+            metrics.block_height = height;
+            METRICS_HISTORY
+                .save(
+                    dependencies.as_mut().storage,
+                    (&validator, U64Key::new(timestamp)),
+                    &metrics,
+                )
+                .unwrap();
+        }
+
+        let metrics = query_validator_metrics_by_height(
+            dependencies.as_ref(),
+            validator.clone(),
+            50,
+            150,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].timestamp, TEST_TIMESTAMP_2);
+
+        let metrics = query_validator_metrics_by_height(
+            dependencies.as_ref(),
+            validator.clone(),
+            100,
+            200,
+            Some(TEST_TIMESTAMP_2),
+            None,
+        )
+        .unwrap();
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].block_height, 200);
+
+        assert!(query_validator_metrics_by_height(
+            dependencies.as_ref(),
+            validator,
+            200,
+            100,
+            None,
+            None
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_keeper_recording() {
+        let mut dependencies = initiate_test_validators_and_metrics();
+        let env = mock_env();
+        let keeper = mock_info("keeper", &[]);
+        let block_time = env.block.time.seconds();
+        let midnight = block_time - block_time % 86400;
+        validators()
+            .remove(
+                dependencies.as_mut().storage,
+                &Addr::unchecked(TEST_VALIDATOR_OPR_ADDR_2),
+            )
+            .unwrap();
+        METRICS_HISTORY.remove(
+            dependencies.as_mut().storage,
+            (
+                &Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
+                U64Key::new(TEST_TIMESTAMP_1),
+            ),
+        );
+        STATE
+            .update(dependencies.as_mut().storage, |mut s| -> StdResult<_> {
+                s.cron_timestamps = vec![midnight - 100];
+                Ok(s)
+            })
+            .unwrap();
+        set_test_staking(&mut dependencies, &env, 100);
+        dependencies
+            .querier
+            .update_balance(env.contract.address.clone(), coins(15, TEST_DENOM));
+        let record_msg = ExecuteMsg::RecordMetrics { timestamp: None };
+
+        let err = execute(
+            dependencies.as_mut(),
+            env.clone(),
+            keeper.clone(),
+            record_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::TimestampRequired {}));
+
+        let keeper_incentive = KeeperIncentive {
+            tip_per_batch: Uint128::new(10),
+            max_tipped_batches_per_cron: 1,
+        };
+        let err = update_keeper_incentive(
+            dependencies.as_mut(),
+            get_test_msg_info(),
+            Some(keeper_incentive.clone()),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::KeeperModeRequiresCronSchedule {}
+        ));
+        update_cron_schedule(
+            dependencies.as_mut(),
+            get_test_msg_info(),
+            Some(CronSchedule {
+                interval: 86400,
+                tolerance: 300,
+            }),
+        )
+        .unwrap();
+        let err = execute(
+            dependencies.as_mut(),
+            env.clone(),
+            keeper.clone(),
+            record_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        update_keeper_incentive(
+            dependencies.as_mut(),
+            get_test_msg_info(),
+            Some(keeper_incentive),
+        )
+        .unwrap();
+        // the last cron was recorded less than an interval ago
+        let err = execute(
+            dependencies.as_mut(),
+            env.clone(),
+            keeper.clone(),
+            record_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::CronIntervalNotElapsed {}));
+
+        STATE
+            .update(dependencies.as_mut().storage, |mut s| -> StdResult<_> {
+                s.cron_timestamps = vec![midnight - 86400];
+                Ok(s)
+            })
+            .unwrap();
+        let res = execute(
+            dependencies.as_mut(),
+            env.clone(),
+            keeper.clone(),
+            record_msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "keeper".to_string(),
+                amount: coins(10, TEST_DENOM),
+            })
+        );
+        assert!(METRICS_HISTORY.has(
+            &dependencies.storage,
+            (
+                &Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
+                U64Key::new(midnight)
+            ),
+        ));
+
+        // the cron is complete, nothing left to record or tip
+        let res = execute(dependencies.as_mut(), env, keeper, record_msg).unwrap();
+        assert!(res.messages.is_empty());
+    }
+
+    #[test]
+    fn test_keeper_tip_needs_balance() {
+        let mut dependencies = instantiate_test_contract();
+        let env = mock_env();
+        let keeper = Addr::unchecked("keeper");
+        dependencies
+            .querier
+            .update_balance(env.contract.address.clone(), coins(5, TEST_DENOM));
+        CONFIG
+            .update(dependencies.as_mut().storage, |mut c| -> StdResult<_> {
+                c.keeper_incentive = Some(KeeperIncentive {
+                    tip_per_batch: Uint128::new(10),
+                    max_tipped_batches_per_cron: 2,
+                });
+                Ok(c)
+            })
+            .unwrap();
+        let cron = CronRecord {
+            timestamp: TEST_TIMESTAMP_1,
+            total_validators: 1,
+            recorded: 0,
+            skipped: vec![],
+            started_at_height: env.block.height,
+            started_at_time: env.block.time.seconds(),
+            finished_at_height: None,
+            finished_at_time: None,
+            next_insertion_order: 0,
+            tipped_batches: 0,
+        };
+        CRON_RECORDS
+            .save(
+                dependencies.as_mut().storage,
+                U64Key::new(TEST_TIMESTAMP_1),
+                &cron,
+            )
+            .unwrap();
+
+        let tip = get_keeper_tip(dependencies.as_mut(), &env, &keeper, TEST_TIMESTAMP_1).unwrap();
+        assert!(tip.is_none());
+
+        dependencies
+            .querier
+            .update_balance(env.contract.address.clone(), coins(15, TEST_DENOM));
+        let tip = get_keeper_tip(dependencies.as_mut(), &env, &keeper, TEST_TIMESTAMP_1).unwrap();
+        assert_eq!(
+            tip,
+            Some(BankMsg::Send {
+                to_address: keeper.to_string(),
+                amount: coins(10, TEST_DENOM),
+            })
+        );
+    }
+
+    #[test]
+    fn test_claim_rewards_keeps_rewards_continuous() {
+        let mut dependencies = initiate_test_validator_with_previous_metrics(100, |previous| {
+            previous.rewards = Decimal::one();
+            previous.rewards_in_coins = coins(1, TEST_DENOM);
+        });
+        let env = mock_env();
+        let validator = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
+        let set_staking = |dependencies: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                           delegated: u128,
+                           rewards: u128| {
+            set_test_staking_entries(
+                dependencies,
+                &[TEST_VALIDATOR_OPR_ADDR],
+                &[
+                    test_delegation(
+                        &env.contract.address,
+                        TEST_VALIDATOR_OPR_ADDR,
+                        delegated,
+                        rewards,
+                    ),
+                    test_delegation(
+                        &Addr::unchecked(TEST_VALIDATOR_ACC_ADDR),
+                        TEST_VALIDATOR_OPR_ADDR,
+                        1000,
+                        0,
+                    ),
+                ],
+            );
+        };
+
+        set_staking(&mut dependencies, 100, 3);
+        let res = claim_rewards(
+            dependencies.as_mut(),
+            env.clone(),
+            get_test_msg_info(),
+            None,
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages
+                .iter()
+                .map(|m| m.msg.clone())
+                .collect::<Vec<CosmosMsg>>(),
+            vec![
+                CosmosMsg::Distribution(DistributionMsg::WithdrawDelegatorReward {
+                    validator: TEST_VALIDATOR_OPR_ADDR.to_string(),
+                }),
+                CosmosMsg::Staking(StakingMsg::Delegate {
+                    validator: TEST_VALIDATOR_OPR_ADDR.to_string(),
+                    amount: Coin::new(3, TEST_DENOM),
+                }),
+            ]
+        );
+
+        // the claimed 3 are restaked and 2 more accumulate before the next cron
+        set_staking(&mut dependencies, 103, 2);
+        let timestamp = TEST_TIMESTAMP_2 + 1;
+        record_validator_metrics(dependencies.as_mut(), env, get_test_msg_info(), timestamp)
+            .unwrap();
+
+        let metrics = METRICS_HISTORY
+            .load(&dependencies.storage, (&validator, U64Key::new(timestamp)))
+            .unwrap();
+        // 1 before, 2 more until the claim and 2 since
+        assert_eq!(metrics.rewards, Decimal::from_ratio(5_u128, 1_u128));
+        assert_eq!(metrics.slashing_pointer, Decimal::one());
+        assert!(REWARDS_BASELINES
+            .may_load(&dependencies.storage, &validator)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_claim_rewards_once_per_validator() {
+        let mut dependencies = initiate_test_validator_with_previous_metrics(100, |_| {});
+        let validator = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
+
+        let res = claim_rewards(
+            dependencies.as_mut(),
+            mock_env(),
+            get_test_msg_info(),
+            Some(vec![validator.clone(), validator.clone()]),
+            false,
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        let baseline = REWARDS_BASELINES
+            .load(&dependencies.storage, &validator)
+            .unwrap();
+        assert_eq!(baseline.claimed, coins(1, TEST_DENOM));
+    }
+
+    #[test]
+    fn test_record_metrics_handles_rewards_reset() {
+        // accumulated rewards are now 1, below the 5 of the previous record
+        let mut dependencies = initiate_test_validator_with_previous_metrics(100, |previous| {
+            previous.rewards = Decimal::from_ratio(5_u128, 1_u128);
+            previous.rewards_in_coins = coins(5, TEST_DENOM);
+        });
+        let env = mock_env();
+        let validator = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
+
+        let timestamp = TEST_TIMESTAMP_2 + 1;
+        let res =
+            record_validator_metrics(dependencies.as_mut(), env, get_test_msg_info(), timestamp)
+                .unwrap();
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "rewards_reset");
+
+        let metrics = METRICS_HISTORY
+            .load(&dependencies.storage, (&validator, U64Key::new(timestamp)))
+            .unwrap();
+        assert!(metrics.rewards_reset);
+        assert_eq!(metrics.rewards, Decimal::from_ratio(6_u128, 1_u128));
+    }
+
+    #[test]
+    fn test_replace_validator() {
+        let mut dependencies = initiate_test_validators_and_metrics();
+        let env = mock_env();
+        let old = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
+        let new = Addr::unchecked("valid0003");
+        // the slashing pointer of the final record is computed against this delegation
+        METRICS_HISTORY
+            .update(
+                dependencies.as_mut().storage,
+                (&old, U64Key::new(TEST_TIMESTAMP_1)),
+                |metrics| -> StdResult<_> {
+                    let mut metrics = metrics.unwrap();
+                    metrics.delegated_amount = Uint128::new(100);
+                    Ok(metrics)
+                },
+            )
+            .unwrap();
+        set_test_staking_entries(
+            &mut dependencies,
+            &[old.as_str(), new.as_str()],
+            &[
+                test_delegation(&env.contract.address, old.as_str(), 100, 2),
+                test_delegation(
+                    &Addr::unchecked(TEST_VALIDATOR_ACC_ADDR),
+                    old.as_str(),
+                    1000,
+                    0,
+                ),
+            ],
+        );
+
+        let err = replace_validator(
+            dependencies.as_mut(),
+            env.clone(),
+            get_test_msg_info(),
+            old.clone(),
+            Addr::unchecked(TEST_VALIDATOR_OPR_ADDR_2),
+            "validacc003".to_string(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ValidatorDoesNotExist {}));
+
+        let res = replace_validator(
+            dependencies.as_mut(),
+            env.clone(),
+            get_test_msg_info(),
+            old.clone(),
+            new.clone(),
+            "validacc003".to_string(),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key.eq("accumulated_rewards") && a.value.eq(&format!("2{}", TEST_DENOM))));
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Staking(StakingMsg::Redelegate {
+                src_validator: old.to_string(),
+                dst_validator: new.to_string(),
+                amount: Coin::new(100, TEST_DENOM),
+            })
+        );
+
+        assert!(validators()
+            .may_load(&dependencies.storage, &old)
+            .unwrap()
+            .is_none());
+        let new_validator = validators().load(&dependencies.storage, &new).unwrap();
+        assert_eq!(new_validator.insertion_order, 2);
+        let state = STATE.load(&dependencies.storage).unwrap();
+        assert_eq!(state.total_validators, 2);
+        assert_eq!(state.next_validator_insertion_order, 3);
+        // history of the replaced validator is kept and closed with the withdrawn rewards
+        assert!(METRICS_HISTORY.has(&dependencies.storage, (&old, U64Key::new(TEST_TIMESTAMP_1))));
+        let final_metrics = METRICS_HISTORY
+            .load(
+                &dependencies.storage,
+                (&old, U64Key::new(env.block.time.seconds())),
+            )
+            .unwrap();
+        assert_eq!(final_metrics.rewards_in_coins, coins(2, TEST_DENOM));
+        assert_eq!(final_metrics.delegated_amount, Uint128::new(100));
+    }
+
+    #[test]
+    fn test_unbonding_ledger() {
+        let mut dependencies = initiate_test_validators_and_metrics();
+        let mut env = mock_env();
+        set_test_staking_entries(
+            &mut dependencies,
+            &[],
+            &[
+                test_delegation(&env.contract.address, TEST_VALIDATOR_OPR_ADDR, 10, 0),
+                test_delegation(&env.contract.address, TEST_VALIDATOR_OPR_ADDR_2, 10, 0),
+            ],
+        );
+        let validator = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
+        REWARDS_BASELINES
+            .save(
+                dependencies.as_mut().storage,
+                &validator,
+                &RewardsBaseline {
+                    coins: vec![],
+                    claimed: coins(3, TEST_DENOM),
+                    restaked: Uint128::new(3),
+                    delegated: Uint128::zero(),
+                    undelegated: Uint128::zero(),
+                },
+            )
+            .unwrap();
+
+        remove_validator(
+            dependencies.as_mut(),
+            env.clone(),
+            get_test_msg_info(),
+            Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
+        )
+        .unwrap();
+        assert!(!REWARDS_BASELINES.has(&dependencies.storage, &validator));
+        env.block.time = env.block.time.plus_seconds(10);
+        remove_validator(
+            dependencies.as_mut(),
+            env.clone(),
+            get_test_msg_info(),
+            Addr::unchecked(TEST_VALIDATOR_OPR_ADDR_2),
+        )
+        .unwrap();
+
+        let pending = query_pending_unbondings(dependencies.as_ref(), None, None).unwrap();
+        assert_eq!(pending.unbondings.len(), 2);
+        assert_eq!(pending.total_unbonding, Uint128::new(20));
+        let first = &pending.unbondings[0];
+        assert_eq!(first.validator, Addr::unchecked(TEST_VALIDATOR_OPR_ADDR));
+        assert_eq!(first.amount, Coin::new(10, TEST_DENOM));
+        assert_eq!(
+            first.completes_at,
+            first.started_at + default_unbonding_period()
+        );
+        let page = query_pending_unbondings(dependencies.as_ref(), Some(first.id), None).unwrap();
+        assert_eq!(page.unbondings.len(), 1);
+
+        // only the first one has matured
+        env.block.time = env.block.time.plus_seconds(default_unbonding_period() - 5);
+        sweep_matured_unbondings(
+            dependencies.as_mut(),
+            env.clone(),
+            get_test_msg_info(),
+            None,
+        )
+        .unwrap();
+        let pending = query_pending_unbondings(dependencies.as_ref(), None, None).unwrap();
+        assert_eq!(pending.unbondings.len(), 1);
+        assert_eq!(
+            pending.unbondings[0].validator,
+            Addr::unchecked(TEST_VALIDATOR_OPR_ADDR_2)
+        );
+        assert_eq!(pending.total_unbonding, Uint128::new(10));
+
+        // an unbonding period past the end of time never completes
+        let mut state = STATE.load(&dependencies.storage).unwrap();
+        let mut config = CONFIG.load(&dependencies.storage).unwrap();
+        config.unbonding_period = u64::MAX;
+        let unbonding = save_unbonding(
+            dependencies.as_mut().storage,
+            &mut state,
+            &config,
+            &env,
+            &Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
+            Coin::new(10, TEST_DENOM),
+        )
+        .unwrap();
+        assert_eq!(unbonding.completes_at, u64::MAX);
+    }
+
+    #[test]
+    fn test_top_up_and_reduce_stake() {
+        let mut dependencies = initiate_test_validators_and_metrics();
+        let env = mock_env();
+        let validator = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
+
+        let res = top_up_stake(
+            dependencies.as_mut(),
+            env.clone(),
+            mock_info(TEST_OWNER_ADDR, &coins(15, TEST_DENOM)),
+            validator.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Staking(StakingMsg::Delegate {
+                validator: validator.to_string(),
+                amount: Coin::new(15, TEST_DENOM),
+            })
+        );
+        let err = top_up_stake(
+            dependencies.as_mut(),
+            env.clone(),
+            mock_info(TEST_OWNER_ADDR, &coins(15, "uusd")),
+            validator.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFundsDenom { .. }));
+        let staked = |dependencies: &OwnedDeps<MockStorage, MockApi, MockQuerier>| {
+            validators()
+                .load(&dependencies.storage, &validator)
+                .unwrap()
+                .staked_amount
+        };
+        assert_eq!(staked(&dependencies), Uint128::new(25));
+        // slashed from 25 to 22, the undelegation is bound by what is actually delegated
+        set_test_staking(&mut dependencies, &env, 22);
+
+        let reduce = |dependencies: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                      amount: u128| {
+            reduce_stake(
+                dependencies.as_mut(),
+                env.clone(),
+                get_test_msg_info(),
+                validator.clone(),
+                Uint128::new(amount),
+            )
+        };
+        let err = reduce(&mut dependencies, 23).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientFunds {}));
+        let err = reduce(&mut dependencies, 22).unwrap_err();
+        assert!(matches!(err, ContractError::CannotReduceStakeToZero {}));
+        let res = reduce(&mut dependencies, 20).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Staking(StakingMsg::Undelegate {
+                validator: validator.to_string(),
+                amount: Coin::new(20, TEST_DENOM),
+            })
+        );
+        assert_eq!(staked(&dependencies), Uint128::new(5));
+        let pending = query_pending_unbondings(dependencies.as_ref(), None, None).unwrap();
+        assert_eq!(pending.total_unbonding, Uint128::new(20));
+
+        // removal undelegates the actual delegation, restaked rewards included
+        set_test_staking_entries(
+            &mut dependencies,
+            &[],
+            &[test_delegation(
+                &env.contract.address,
+                validator.as_str(),
+                7,
+                0,
+            )],
+        );
+        let res = remove_validator(
+            dependencies.as_mut(),
+            env.clone(),
+            get_test_msg_info(),
+            validator.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Staking(StakingMsg::Undelegate {
+                validator: validator.to_string(),
+                amount: Coin::new(7, TEST_DENOM),
+            })
+        );
+        let pending = query_pending_unbondings(dependencies.as_ref(), None, None).unwrap();
+        assert_eq!(pending.total_unbonding, Uint128::new(27));
     }
 
     #[test]
-    fn test_record_metrics() {
-        let mut deps = initiate_test_validators_and_metrics();
-        let info = get_test_msg_info();
+    fn test_stake_changes_are_not_slashing() {
+        let mut dependencies = initiate_test_validator_with_previous_metrics(100, |_| {});
         let env = mock_env();
-        let timestamp = 10;
+        let validator = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
 
-        let _res = record_validator_metrics(deps.as_mut(), env, info, timestamp);
-    }
+        reduce_stake(
+            dependencies.as_mut(),
+            env.clone(),
+            get_test_msg_info(),
+            validator.clone(),
+            Uint128::new(40),
+        )
+        .unwrap();
+        top_up_stake(
+            dependencies.as_mut(),
+            env.clone(),
+            mock_info(TEST_OWNER_ADDR, &coins(20, TEST_DENOM)),
+            validator.clone(),
+        )
+        .unwrap();
+        // 100 - 40 + 20
+        set_test_staking(&mut dependencies, &env, 80);
 
-    #[test]
-    fn test_get_all_validator_metrics() {
-        let deps = mock_dependencies(&[]);
-        let validator = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
-        let _res = query_all_validator_metrics(deps.as_ref(), validator);
+        let timestamp = TEST_TIMESTAMP_2 + 1;
+        let res =
+            record_validator_metrics(dependencies.as_mut(), env, get_test_msg_info(), timestamp)
+                .unwrap();
+        assert!(res.events.iter().all(|event| event.ty != "slashing"));
+        assert!(
+            query_slashing_events(dependencies.as_ref(), None, None, None)
+                .unwrap()
+                .is_empty()
+        );
+        let metrics = METRICS_HISTORY
+            .load(&dependencies.storage, (&validator, U64Key::new(timestamp)))
+            .unwrap();
+        assert_eq!(metrics.delegated_amount, Uint128::new(80));
+        assert_eq!(metrics.slashing_pointer, Decimal::one());
+        // the mock reports 1 accumulated throughout, withdrawn by both changes and 1 more since
+        assert_eq!(metrics.rewards, Decimal::from_ratio(3_u128, 1_u128));
     }
 
     #[test]
-    fn test_add_validator() {
-        let mut deps = mock_dependencies(&[]);
-        let info = mock_info("creator", &[]);
-        let validator_opr = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
-        let validator_acc = Addr::unchecked(TEST_VALIDATOR_ACC_ADDR).to_string();
-        let _res = add_validator(deps.as_mut(), info, validator_opr, validator_acc);
-    }
+    fn test_withdraw_funds() {
+        let mut dependencies = instantiate_test_contract();
+        let env = mock_env();
+        dependencies.querier.update_balance(
+            env.contract.address.clone(),
+            vec![Coin::new(100, TEST_DENOM), Coin::new(7, "uusd")],
+        );
+        let withdraw = |dependencies: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                        info: MessageInfo,
+                        amount: u128,
+                        denom: Option<&str>,
+                        recipient: Option<&str>| {
+            withdraw_funds(
+                dependencies.as_mut(),
+                env.clone(),
+                info,
+                Uint128::new(amount),
+                denom.map(String::from),
+                recipient.map(String::from),
+            )
+        };
 
-    #[test]
-    fn test_delete_metrics_for_validator() {
-        //initiate state
-        let mut dependencies = initiate_test_validators_and_metrics();
+        let err = withdraw(&mut dependencies, mock_info("other", &[]), 10, None, None).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
 
-        let state = STATE.load(&dependencies.storage).unwrap();
-        let metrics = METRICS_HISTORY.load(
-            &dependencies.storage,
-            (
-                &Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
-                U64Key::from(TEST_TIMESTAMP_1),
-            ),
+        let err = withdraw(&mut dependencies, get_test_msg_info(), 101, None, None).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientFunds {}));
+        let res = withdraw(&mut dependencies, get_test_msg_info(), 60, None, None).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: TEST_OWNER_ADDR.to_string(),
+                amount: coins(60, TEST_DENOM),
+            })
         );
+        assert!(withdraw(
+            &mut dependencies,
+            get_test_msg_info(),
+            7,
+            Some("uusd"),
+            None
+        )
+        .is_ok());
 
-        // check if state has the timestamp
-        assert_eq!(2, state.cron_timestamps.len());
-        // check if metrics response is giving data
-        assert!(metrics.is_ok());
-        // check if remove validator removes both validator and metrics
-        let result = delete_metrics_for_validator(
+        let err = withdraw(
+            &mut dependencies,
+            get_test_msg_info(),
+            7,
+            Some("uusd"),
+            Some("treasury"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::RecipientNotAllowed {}));
+        grant_role(
             dependencies.as_mut(),
             get_test_msg_info(),
-            Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
-            0,
-            1,
+            Role::Admin,
+            "admin".to_string(),
+        )
+        .unwrap();
+        assert!(allow_withdrawal_recipient(
+            dependencies.as_mut(),
+            mock_info("admin", &[]),
+            "treasury".to_string()
+        )
+        .is_err());
+        allow_withdrawal_recipient(
+            dependencies.as_mut(),
+            get_test_msg_info(),
+            "treasury".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            query_withdrawal_recipients(dependencies.as_ref()).unwrap(),
+            vec![Addr::unchecked("treasury")]
+        );
+        let res = withdraw(
+            &mut dependencies,
+            mock_info("admin", &[]),
+            7,
+            Some("uusd"),
+            Some("treasury"),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(7, "uusd"),
+            })
         );
-
-        let timestamps_left = result
-            .unwrap()
-            .attributes
-            .into_iter()
-            .find(|item| item.key.eq("timestamps_left"))
-            .unwrap()
-            .value;
-
-        assert_eq!(timestamps_left, "1");
     }
 
-    //tests if the metrics and timestamp exists, and upon deletion of timestamp both go away
     #[test]
-    fn test_delete_metrics_for_timestamp() {
-        //initiate state
+    fn test_resume_cron() {
         let mut dependencies = initiate_test_validators_and_metrics();
-
-        let state = STATE.load(&dependencies.storage).unwrap();
-        let metrics = METRICS_HISTORY.load(
-            &dependencies.storage,
+        let env = mock_env();
+        CONFIG
+            .update(dependencies.as_mut().storage, |mut c| -> StdResult<_> {
+                c.batch_size = 1;
+                Ok(c)
+            })
+            .unwrap();
+        METRICS_HISTORY.remove(
+            dependencies.as_mut().storage,
             (
                 &Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
-                U64Key::from(TEST_TIMESTAMP_1),
+                U64Key::new(TEST_TIMESTAMP_1),
             ),
         );
+        set_test_staking_entries(
+            &mut dependencies,
+            &[TEST_VALIDATOR_OPR_ADDR, TEST_VALIDATOR_OPR_ADDR_2],
+            &[
+                test_delegation(&env.contract.address, TEST_VALIDATOR_OPR_ADDR, 100, 0),
+                test_delegation(&env.contract.address, TEST_VALIDATOR_OPR_ADDR_2, 100, 0),
+                test_delegation(
+                    &Addr::unchecked(TEST_VALIDATOR_ACC_ADDR),
+                    TEST_VALIDATOR_OPR_ADDR,
+                    100,
+                    0,
+                ),
+                test_delegation(
+                    &Addr::unchecked(TEST_VALIDATOR_ACC_ADDR_2),
+                    TEST_VALIDATOR_OPR_ADDR_2,
+                    100,
+                    0,
+                ),
+            ],
+        );
 
-        // check if state has the timestamp
-        assert_eq!(2, state.cron_timestamps.len());
-        // check if metrics response is giving data
-        assert!(metrics.is_ok());
-
-        // delete one validator from this timestamp metrics
-        let result = delete_metrics_for_timestamp(
+        // the bot dies after the first batch
+        let timestamp = TEST_TIMESTAMP_2 + 1;
+        record_validator_metrics(
             dependencies.as_mut(),
+            env.clone(),
             get_test_msg_info(),
-            TEST_TIMESTAMP_1,
-            0,
-            1,
-        );
-
-        // check if result is okay
-        assert!(result.as_ref().ok().is_some());
-        let validators_left = result
-            .unwrap()
-            .attributes
-            .into_iter()
-            .find(|attribute| attribute.key.eq("validators_left"))
-            .unwrap()
-            .value;
-
-        // assert that one validator is still left for the timestamp
-        assert_eq!(validators_left, "1");
-
-        // delete second validator from this timestamp metrics
-        let result = delete_metrics_for_timestamp(
+            timestamp,
+        )
+        .unwrap();
+        let incomplete = query_incomplete_crons(dependencies.as_ref(), None, None).unwrap();
+        assert_eq!(incomplete.len(), 1);
+        assert_eq!(incomplete[0].timestamp, timestamp);
+
+        CONFIG
+            .update(dependencies.as_mut().storage, |mut c| -> StdResult<_> {
+                c.refuse_new_cron_when_incomplete = true;
+                Ok(c)
+            })
+            .unwrap();
+        let err = record_validator_metrics(
             dependencies.as_mut(),
+            env.clone(),
             get_test_msg_info(),
-            TEST_TIMESTAMP_1,
-            1,
-            1,
-        );
-
-        // check if result is okay
-        assert!(result.as_ref().ok().is_some());
-        let validators_left = result
-            .unwrap()
-            .attributes
-            .into_iter()
-            .find(|attribute| attribute.key.eq("validators_left"))
-            .unwrap()
-            .value;
-
-        // assert that all validators are deleted for this timestamp
-        assert_eq!(validators_left, "0");
+            timestamp + 1,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::CronIncomplete { timestamp: t } if t == timestamp));
 
-        let metrics = METRICS_HISTORY.load(
+        resume_cron(
+            dependencies.as_mut(),
+            env.clone(),
+            get_test_msg_info(),
+            timestamp,
+        )
+        .unwrap();
+        assert!(METRICS_HISTORY.has(
             &dependencies.storage,
             (
-                &Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
-                U64Key::from(TEST_TIMESTAMP_1),
+                &Addr::unchecked(TEST_VALIDATOR_OPR_ADDR_2),
+                U64Key::new(timestamp)
             ),
-        );
+        ));
+        let status = query_cron_status(dependencies.as_ref(), timestamp).unwrap();
+        assert_eq!(status.recorded, 2);
+        assert!(status.is_complete);
+        assert!(query_incomplete_crons(dependencies.as_ref(), None, None)
+            .unwrap()
+            .is_empty());
 
-        // check if metrics response is giving error, due to this metric not being available
-        assert!(metrics.is_err());
+        // the resumed cron is the current one, recording it again must not record anyone twice
+        record_validator_metrics(
+            dependencies.as_mut(),
+            env.clone(),
+            get_test_msg_info(),
+            timestamp,
+        )
+        .unwrap();
+        let status = query_cron_status(dependencies.as_ref(), timestamp).unwrap();
+        assert_eq!(status.recorded, 2);
+        let aggregate = query_timestamp_aggregate(dependencies.as_ref(), timestamp).unwrap();
+        assert_eq!(aggregate.validators_recorded, 2);
+        assert_eq!(aggregate.total_delegated, Uint128::new(200));
+
+        let err = resume_cron(
+            dependencies.as_mut(),
+            env.clone(),
+            get_test_msg_info(),
+            timestamp,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::CronAlreadyComplete {}));
+        record_validator_metrics(
+            dependencies.as_mut(),
+            env,
+            get_test_msg_info(),
+            timestamp + 1,
+        )
+        .unwrap();
     }
 
     #[test]
-    fn test_delete_timestamp() {
-        let mut dependencies = initiate_test_validators_and_metrics();
-        delete_metrics_for_timestamp(
+    fn test_record_metrics_tracks_status() {
+        let mut dependencies = initiate_test_validator_with_previous_metrics(100, |_| {});
+        let env = mock_env();
+        let validator = Addr::unchecked(TEST_VALIDATOR_OPR_ADDR);
+
+        let timestamp = TEST_TIMESTAMP_2 + 1;
+        record_validator_metrics(
             dependencies.as_mut(),
+            env.clone(),
             get_test_msg_info(),
-            TEST_TIMESTAMP_1,
-            0,
-            2,
+            timestamp,
+        )
+        .unwrap();
+
+        // validator drops out of the set and our delegation gets slashed
+        set_test_staking_entries(
+            &mut dependencies,
+            &[],
+            &[test_delegation(
+                &env.contract.address,
+                TEST_VALIDATOR_OPR_ADDR,
+                99,
+                0,
+            )],
         );
+        record_validator_metrics(
+            dependencies.as_mut(),
+            env,
+            get_test_msg_info(),
+            timestamp + 1,
+        )
+        .unwrap();
 
-        let result = remove_timestamp(dependencies.as_mut(), get_test_msg_info(), TEST_TIMESTAMP_1);
-    }
-
-    fn initiate_test_validators_and_metrics() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
-        let mut dependencies = instantiate_test_contract();
-
-        // initiate state to have two timestamp metrics for two validators
-        let _msg = get_test_msg_info();
-
-        // initiate state
-        let _ = STATE.update(dependencies.as_mut().storage, |mut s| -> StdResult<_> {
-            s.validators = get_test_validators();
-            s.cron_timestamps = vec![TEST_TIMESTAMP_1, TEST_TIMESTAMP_2];
-            Ok(s)
-        });
-
-        // initiate metrics
-        let _ = METRICS_HISTORY.save(
-            dependencies.as_mut().storage,
-            (
-                &Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
-                U64Key::from(TEST_TIMESTAMP_1),
-            ),
-            &get_test_metrics(TEST_VALIDATOR_OPR_ADDR, TEST_TIMESTAMP_1),
+        let history =
+            query_validator_status_history(dependencies.as_ref(), validator.clone(), None, None)
+                .unwrap();
+        assert_eq!(
+            history,
+            vec![
+                ValidatorStatusRecord {
+                    timestamp,
+                    status: ValidatorStatus::Active,
+                },
+                ValidatorStatusRecord {
+                    timestamp: timestamp + 1,
+                    status: ValidatorStatus::Jailed,
+                },
+            ]
         );
-        dependencies
+        assert!(METRICS_HISTORY
+            .may_load(
+                &dependencies.storage,
+                (&validator, U64Key::new(timestamp + 1))
+            )
+            .unwrap()
+            .is_none());
     }
 
-    fn get_test_msg_info() -> MessageInfo {
-        MessageInfo {
-            sender: Addr::unchecked(TEST_OWNER_ADDR),
-            funds: vec![Coin::new(1000, TEST_DENOM)],
-        }
+    // Delegations of the contract and of the validator account to TEST_VALIDATOR_OPR_ADDR
+    fn set_test_staking(
+        dependencies: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        env: &Env,
+        delegated_amount: u128,
+    ) {
+        set_test_staking_entries(
+            dependencies,
+            &[TEST_VALIDATOR_OPR_ADDR],
+            &[
+                test_delegation(
+                    &env.contract.address,
+                    TEST_VALIDATOR_OPR_ADDR,
+                    delegated_amount,
+                    1,
+                ),
+                test_delegation(
+                    &Addr::unchecked(TEST_VALIDATOR_ACC_ADDR),
+                    TEST_VALIDATOR_OPR_ADDR,
+                    1000,
+                    1,
+                ),
+            ],
+        );
     }
 
-    fn get_test_metrics(validator_addr: &str, timestamp: u64) -> ValidatorMetrics {
-        ValidatorMetrics {
-            operator_addr: Addr::unchecked(validator_addr),
-            rewards: Default::default(),
-            delegated_amount: Default::default(),
-            self_delegated_amount: Default::default(),
-            slashing_pointer: Default::default(),
-            commission: Default::default(),
-            max_commission: Default::default(),
-            timestamp: timestamp,
-            rewards_in_coins: vec![],
-        }
+    // `active_validators` are in the validator set at a 5% commission, delegations can also be to
+    // validators out of the set
+    fn set_test_staking_entries(
+        dependencies: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        active_validators: &[&str],
+        delegations: &[cosmwasm_std::FullDelegation],
+    ) {
+        let validators: Vec<cosmwasm_std::Validator> = active_validators
+            .iter()
+            .map(|address| cosmwasm_std::Validator {
+                address: address.to_string(),
+                commission: Decimal::percent(5),
+                max_commission: Decimal::percent(20),
+                max_change_rate: Decimal::percent(1),
+            })
+            .collect();
+        dependencies
+            .querier
+            .update_staking(TEST_DENOM, &validators, delegations);
     }
 
-    fn get_test_validators() -> Vec<ValidatorAccounts> {
-        vec![
-            ValidatorAccounts {
-                operator_address: Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
-                account_address: Addr::unchecked(TEST_VALIDATOR_ACC_ADDR),
-            },
-            ValidatorAccounts {
-                operator_address: Addr::unchecked(TEST_VALIDATOR_OPR_ADDR_2),
-                account_address: Addr::unchecked(TEST_VALIDATOR_ACC_ADDR_2),
+    fn test_delegation(
+        delegator: &Addr,
+        validator: &str,
+        amount: u128,
+        rewards: u128,
+    ) -> cosmwasm_std::FullDelegation {
+        cosmwasm_std::FullDelegation {
+            delegator: delegator.clone(),
+            validator: validator.to_string(),
+            amount: Coin::new(amount, TEST_DENOM),
+            can_redelegate: Coin::new(amount, TEST_DENOM),
+            accumulated_rewards: if rewards == 0 {
+                vec![]
+            } else {
+                coins(rewards, TEST_DENOM)
             },
-        ]
+        }
     }
 
     #[test]
@@ -1456,7 +5345,8 @@ mod tests {
             dependencies.as_mut(),
             get_test_msg_info(),
             Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
-        );
+        )
+        .unwrap();
 
         let updated_state = get_off_chain_state(dependencies.as_ref()).unwrap();
 
@@ -1494,7 +5384,8 @@ mod tests {
             dependencies.as_mut(),
             get_test_msg_info(),
             Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
-        );
+        )
+        .unwrap();
 
         let off_chain_metrics_result = get_off_chain_metrics(
             dependencies.as_ref(),
@@ -1537,7 +5428,7 @@ mod tests {
     fn test_get_off_chain_timestamps() {
         let mut dependencies = instantiate_test_contract();
 
-        let mut test_timestamp = 100000;
+        let test_timestamp = 100000;
         let mut test_timestamp_meta_data = get_test_off_chain_timestamp_meta_data();
 
         test_timestamp_meta_data.timestamp = test_timestamp;
@@ -1560,7 +5451,7 @@ mod tests {
     fn test_delete_off_chain_metrics_timestamp() {
         let mut dependencies = instantiate_test_contract();
 
-        let mut test_timestamp = 100000;
+        let test_timestamp = 100000;
         let mut test_timestamp_meta_data = get_test_off_chain_timestamp_meta_data();
 
         test_timestamp_meta_data.timestamp = test_timestamp;
@@ -1582,7 +5473,7 @@ mod tests {
 
         assert!(saved_details.is_ok());
 
-        let mut test_metric = get_test_off_chain_validator_metric().clone();
+        let test_metric = get_test_off_chain_validator_metric();
 
         let saved_data = add_off_chain_validator_metrics(
             dependencies.as_mut(),
@@ -1590,6 +5481,8 @@ mod tests {
             test_timestamp,
             vec![test_metric],
         );
+
+        assert!(saved_data.is_ok());
         let delete_off_chain_timestamp = remove_off_chain_metrics_for_timestamp(
             dependencies.as_mut(),
             get_test_msg_info(),
@@ -1608,7 +5501,8 @@ mod tests {
             dependencies.as_mut(),
             get_test_msg_info(),
             Addr::unchecked(TEST_VALIDATOR_OPR_ADDR),
-        );
+        )
+        .unwrap();
 
         let get_off_chain_validators = get_off_chain_validators(dependencies.as_ref());
 
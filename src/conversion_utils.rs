@@ -1,7 +1,7 @@
 use cosmwasm_std::Addr;
 
 pub(crate) fn u64_from_vec_u8(vector: Vec<u8>) -> u64 {
-    let byte_0 = *vector.get(0).unwrap_or(&0);
+    let byte_0 = *vector.first().unwrap_or(&0);
     let byte_1 = *vector.get(1).unwrap_or(&0);
     let byte_2 = *vector.get(2).unwrap_or(&0);
     let byte_3 = *vector.get(3).unwrap_or(&0);
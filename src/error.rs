@@ -21,6 +21,15 @@ pub enum ContractError {
     #[error("In sufficient funds for this action")]
     InsufficientFunds {},
 
+    #[error("Only a single coin can be sent")]
+    MultipleCoinsSent {},
+
+    #[error("Expected funds in {expected}, got {found}")]
+    InvalidFundsDenom { expected: String, found: String },
+
+    #[error("Stake can't be reduced to zero, remove the validator instead")]
+    CannotReduceStakeToZero {},
+
     #[error("Something went wrong while getting the delegation??")]
     NoDelegationFound { manager: Addr, validator: Addr },
 
@@ -50,4 +59,52 @@ pub enum ContractError {
 
     #[error("Details for timestamp already recorded")]
     OffChainDetailsAlreadyRecorded,
+
+    #[error("Role is already granted to this address")]
+    RoleAlreadyGranted {},
+
+    #[error("Role is not granted to this address")]
+    RoleNotGranted {},
+
+    #[error("No manager change has been proposed")]
+    NoPendingManager {},
+
+    #[error("Manager proposal has expired")]
+    ManagerProposalExpired {},
+
+    #[error("At least one score weight must be non zero")]
+    InvalidScoreWeights {},
+
+    #[error("Validator voting powers cannot add up to more than the total bonded tokens")]
+    VotingPowerAboveTotalBonded {},
+
+    #[error("Cron {timestamp} is incomplete, it must be resumed before starting a new one")]
+    CronIncomplete { timestamp: u64 },
+
+    #[error("All validators are already recorded for this cron")]
+    CronAlreadyComplete {},
+
+    #[error("Timestamp is required when no cron schedule is configured")]
+    TimestampRequired {},
+
+    #[error("Timestamp is too far from the block time")]
+    TimestampOutsideTolerance {},
+
+    #[error("Cron schedule interval cannot be zero")]
+    InvalidCronSchedule {},
+
+    #[error("Keepers can only record metrics with a cron schedule configured")]
+    KeeperModeRequiresCronSchedule {},
+
+    #[error("Cron interval has not elapsed since the last cron")]
+    CronIntervalNotElapsed {},
+
+    #[error("Delegation can't be redelegated yet, it was itself redelegated recently")]
+    RedelegationNotAvailable {},
+
+    #[error("Recipient is not allowed to receive withdrawals")]
+    RecipientNotAllowed {},
+
+    #[error("Recipient is already allowed to receive withdrawals")]
+    RecipientAlreadyAllowed {},
 }
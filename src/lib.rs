@@ -3,6 +3,7 @@ pub mod contract;
 mod conversion_utils;
 mod error;
 pub mod msg;
+mod scoring;
 pub mod state;
 pub mod util;
 
@@ -1,4 +1,7 @@
-use crate::state::{OffChainValidatorMetrics, OffchainTimestampMetaData};
+use crate::state::{
+    CronSchedule, KeeperIncentive, OffChainValidatorMetrics, OffchainTimestampMetaData, Role,
+    ScoreWeights, SkippedValidator, UnbondingEntry,
+};
 use cosmwasm_std::{Addr, Decimal, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -14,20 +17,53 @@ pub struct InstantiateMsg {
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     RecordMetrics {
-        timestamp: u64,
+        timestamp: Option<u64>, // only checked against block time when a cron schedule is set
     }, // 12:00AM
+    ResumeCron {
+        timestamp: u64,
+    }, // records the validators an older, incomplete cron didn't get to
     AddValidator {
         validator_opr_addr: Addr,
         account_addr: String,
     }, //validator's operator address,validator's account address
     UpdateConfig {
-        batch_size: u64,
+        batch_size: Option<u64>,
+        commission_change_threshold: Option<Decimal>,
+        refuse_new_cron_when_incomplete: Option<bool>,
+        unbonding_period: Option<u64>,
     },
     RemoveValidator {
         validator_oper_addr: Addr,
     },
+    TopUpStake {
+        validator: Addr,
+    }, // delegates the sent vault denom funds on top of the current stake
+    ReduceStake {
+        validator: Addr,
+        amount: Uint128,
+    },
+    ReplaceValidator {
+        old: Addr,            // operator address of the validator to stop tracking
+        new: Addr,            // operator address of the validator to track instead
+        account_addr: String, // account address of the new validator
+    },
     WithdrawFunds {
         amount: Uint128,
+        denom: Option<String>,     // vault denom when not given
+        recipient: Option<String>, // manager when not given, otherwise must be allowed
+    },
+    AllowWithdrawalRecipient {
+        addr: String,
+    },
+    DisallowWithdrawalRecipient {
+        addr: String,
+    },
+    SweepMaturedUnbondings {
+        limit: Option<u32>,
+    },
+    ClaimRewards {
+        validators: Option<Vec<Addr>>, // all validators when not given
+        restake: Option<bool>,         // delegate the vault denom rewards back to the validator
     },
     DeleteMetricsForTimestamp {
         timestamp: u64,
@@ -57,6 +93,36 @@ pub enum ExecuteMsg {
         timestamp: u64,
         validator_metrics: Vec<OffChainValidatorMetrics>,
     },
+    GrantRole {
+        role: Role,
+        addr: String,
+    },
+    RevokeRole {
+        role: Role,
+        addr: String,
+    },
+    ProposeNewManager {
+        new_manager: String,
+        expiry_in_seconds: Option<u64>,
+    },
+    AcceptManagership {},
+    CancelManagerProposal {},
+    UpdateScoreWeights {
+        score_weights: ScoreWeights,
+    },
+    UpdateCronSchedule {
+        cron_schedule: Option<CronSchedule>, // unset to go back to caller supplied timestamps
+    },
+    UpdateKeeperIncentive {
+        keeper_incentive: Option<KeeperIncentive>, // unset to only let metrics recorders record
+    },
+    // Trusted input of the metrics recorder, the 0.16 querier has no pool or validator tokens data to
+    // check it against. Validators without metrics at the timestamp are skipped.
+    RecordVotingPower {
+        timestamp: u64,
+        total_bonded: Uint128,
+        voting_powers: Vec<ValidatorVotingPower>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -71,9 +137,17 @@ pub enum QueryMsg {
         timestamp1: u64,
         timestamp2: u64,
     },
+    GetValidatorMetricsByHeight {
+        addr: Addr,
+        from_height: u64,         // inclusive
+        to_height: u64,           // inclusive
+        start_after: Option<u64>, // timestamp of the last metrics in the previous page
+        limit: Option<u32>,
+    },
     GetValidatorMetricsByTimestamp {
         timestamp: u64,
         addr: Addr,
+        resolution: Option<TimestampResolution>,
     },
     GetValidatorsMetricsByTimestamp {
         timestamp: u64,
@@ -87,11 +161,24 @@ pub enum QueryMsg {
         timestamp2: u64,
         from: u64,
         to: u64,
+        resolution: Option<TimestampResolution>,
     },
     GetAprByValidator {
         timestamp1: u64,
         timestamp2: u64,
         addr: Addr,
+        resolution: Option<TimestampResolution>,
+    },
+    GetSlashingAdjustedAprByValidator {
+        timestamp1: u64,
+        timestamp2: u64,
+        addr: Addr,
+    },
+    // Windows are in seconds, counted back from the latest cron. A window of 0 is since inception,
+    // it starts at the first record of the validator.
+    GetAprWindows {
+        addr: Addr,
+        windows: Vec<u64>,
     },
     GetOffChainValidatorMetrics {
         timestamp: u64,
@@ -103,6 +190,74 @@ pub enum QueryMsg {
         timestamp: u64,
     },
     GetOffChainValidators {},
+    GetValidators {
+        start_after: Option<u64>, // insertion order of the last validator in the previous page
+        limit: Option<u32>,
+    },
+    GetValidatorsByAccount {
+        account_addr: String,
+    },
+    GetRoles {
+        addr: Option<Addr>, // all grants when not given
+    },
+    GetSlashingEvents {
+        validator: Option<Addr>,
+        start_after: Option<u64>, // timestamp of the last event in the previous page
+        limit: Option<u32>,
+    },
+    GetCommissionChanges {
+        validator: Option<Addr>,
+        since: Option<u64>, // timestamp, inclusive
+        limit: Option<u32>,
+    },
+    GetValidatorStatusHistory {
+        addr: Addr,
+        start_after: Option<u64>, // timestamp
+        limit: Option<u32>,
+    },
+    GetVotingPowerHistory {
+        addr: Addr,
+        from: u64, // timestamp, inclusive
+        to: u64,   // timestamp, inclusive
+    },
+    GetWithdrawalRecipients {},
+    GetPendingUnbondings {
+        start_after: Option<u64>, // id of the last entry in the previous page
+        limit: Option<u32>,
+    },
+    GetTimestampAggregate {
+        timestamp: u64,
+    },
+    GetCronStatus {
+        timestamp: u64,
+    },
+    GetIncompleteCrons {
+        start_after: Option<u64>, // timestamp
+        limit: Option<u32>,
+    },
+    GetValidatorScores {
+        timestamp1: u64,
+        timestamp2: u64,
+        start_after: Option<Addr>, // last validator of the previous page, in score order
+        limit: Option<u32>,
+    },
+}
+
+// How a timestamp without recorded metrics is resolved to a stored one
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampResolution {
+    Exact,   // used when no resolution is given
+    Floor,   // latest record at or before the timestamp
+    Ceil,    // earliest record at or after the timestamp
+    Nearest, // closest record, the earlier one on a tie
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ValidatorVotingPower {
+    pub addr: Addr,
+    pub tokens: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -113,8 +268,122 @@ pub struct MigrateMsg {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct ValidatorAprResponse {
+    pub addr: Addr,
+    // recorded timestamps actually used for the apr
+    pub timestamp1: u64,
+    pub timestamp2: u64,
+    pub apr: Decimal,                   // net apr, as seen by delegators
+    pub gross_apr: Option<Decimal>,     // estimated apr before commission, none at 100%
+    pub slashing_adjusted_apr: Decimal, // net apr after principal lost to slashing
+    pub avg_commission: Decimal,        // time weighted over the interval
+    pub start_commission: Decimal,
+    pub end_commission: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SlashingAdjustedAprResponse {
     pub addr: Addr,
     pub apr: Decimal,
+    pub slashing_adjusted_apr: Decimal,
+    pub slashing_ratio: Decimal, // slashing_pointer at timestamp2 / slashing_pointer at timestamp1
+    pub principal_lost: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AprWindowsResponse {
+    pub addr: Addr,
+    pub latest_timestamp: u64,
+    pub windows: Vec<AprWindow>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AprWindow {
+    pub window: u64,
+    // recorded timestamps actually used for the apr
+    pub timestamp1: u64,
+    pub timestamp2: u64,
+    // not set when there is no record before the latest cron for this window, or the delegation at
+    // timestamp1 is zero
+    pub apr: Option<ValidatorAprResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ValidatorScore {
+    pub addr: Addr,
+    pub score: Decimal, // between 0 and 1
+    pub apr: Decimal,
+    pub commission: Decimal,
+    pub max_commission: Decimal,
+    pub self_delegation_ratio: Decimal,
+    pub slashing_ratio: Decimal,
+    pub recorded_ratio: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CommissionChangeResponse {
+    pub validator: Addr,
+    pub timestamp: u64,
+    pub previous_commission: Decimal,
+    pub new_commission: Decimal,
+    pub above_threshold: bool, // against the currently configured threshold
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct VotingPowerRecord {
+    pub timestamp: u64,
+    // not set until recorded for the timestamp
+    pub voting_power: Option<Uint128>,
+    pub network_share: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TimestampAggregateResponse {
+    pub timestamp: u64,
+    pub validators_recorded: u64,
+    pub validators_with_apr: u64, // validators that had a previous record to compute the apr against
+    // not set when no apr could be computed
+    pub mean_apr: Option<Decimal>,
+    pub median_apr: Option<Decimal>,
+    pub min_apr: Option<Decimal>,
+    pub max_apr: Option<Decimal>,
+    pub total_delegated: Uint128,
+    pub total_rewards: Decimal,
+    pub stake_weighted_commission: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CronStatusResponse {
+    pub timestamp: u64,
+    pub total_validators: u64,
+    pub recorded: u64,
+    pub skipped: Vec<SkippedValidator>, // validators out of the active set, no metrics recorded
+    pub started_at_height: u64,
+    pub started_at_time: u64,
+    pub finished_at_height: Option<u64>,
+    pub finished_at_time: Option<u64>,
+    pub is_complete: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PendingUnbondingsResponse {
+    pub unbondings: Vec<UnbondingEntry>,
+    pub total_unbonding: Uint128, // over all entries, not only this page
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RoleGrant {
+    pub role: Role,
+    pub addr: Addr,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
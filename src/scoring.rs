@@ -0,0 +1,159 @@
+use crate::msg::ValidatorScore;
+use crate::state::ScoreWeights;
+use crate::util::{
+    decimal_division_in_256, decimal_multiplication_in_256, decimal_subtraction_in_256,
+    decimal_summation_in_256,
+};
+use cosmwasm_std::{Addr, Decimal};
+
+// Raw per validator numbers over the scoring interval, before normalization
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoreInputs {
+    pub addr: Addr,
+    pub apr: Decimal,
+    pub commission: Decimal,
+    pub max_commission: Decimal,
+    pub self_delegation_ratio: Decimal, // self_delegated_amount / delegated_amount
+    pub slashing_ratio: Decimal,        // slashing_pointer at timestamp2 / at timestamp1
+    pub recorded_ratio: Decimal,        // crons recorded / crons in the interval
+}
+
+// Every component is brought into [0, 1] where higher is better. Apr and self delegation have no
+// natural upper bound, so they are relative to the best validator in the set.
+// Returns the scores sorted from best to worst.
+pub fn compute_scores(inputs: Vec<ScoreInputs>, weights: &ScoreWeights) -> Vec<ValidatorScore> {
+    let max_apr = inputs.iter().map(|i| i.apr).max().unwrap_or_default();
+    let max_self_delegation_ratio = inputs
+        .iter()
+        .map(|i| i.self_delegation_ratio)
+        .max()
+        .unwrap_or_default();
+
+    let mut scores: Vec<ValidatorScore> = inputs
+        .into_iter()
+        .map(|i| {
+            let components = [
+                (weights.apr, relative_to(i.apr, max_apr)),
+                (weights.commission, one_minus(i.commission)),
+                (weights.max_commission, one_minus(i.max_commission)),
+                (
+                    weights.self_delegation,
+                    relative_to(i.self_delegation_ratio, max_self_delegation_ratio),
+                ),
+                (weights.slashing, cap_at_one(i.slashing_ratio)),
+                (weights.recording, cap_at_one(i.recorded_ratio)),
+            ];
+            ValidatorScore {
+                addr: i.addr,
+                score: weighted_average(&components),
+                apr: i.apr,
+                commission: i.commission,
+                max_commission: i.max_commission,
+                self_delegation_ratio: i.self_delegation_ratio,
+                slashing_ratio: i.slashing_ratio,
+                recorded_ratio: i.recorded_ratio,
+            }
+        })
+        .collect();
+
+    scores.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.addr.cmp(&b.addr)));
+    scores
+}
+
+pub fn total_weight(weights: &ScoreWeights) -> Decimal {
+    [
+        weights.apr,
+        weights.commission,
+        weights.max_commission,
+        weights.self_delegation,
+        weights.slashing,
+        weights.recording,
+    ]
+    .iter()
+    .fold(Decimal::zero(), |acc, w| decimal_summation_in_256(acc, *w))
+}
+
+fn weighted_average(components: &[(Decimal, Decimal)]) -> Decimal {
+    let mut weighted_sum = Decimal::zero();
+    let mut weights_sum = Decimal::zero();
+    for (weight, value) in components {
+        weighted_sum =
+            decimal_summation_in_256(weighted_sum, decimal_multiplication_in_256(*weight, *value));
+        weights_sum = decimal_summation_in_256(weights_sum, *weight);
+    }
+    if weights_sum.is_zero() {
+        return Decimal::zero();
+    }
+    decimal_division_in_256(weighted_sum, weights_sum)
+}
+
+fn relative_to(value: Decimal, max: Decimal) -> Decimal {
+    if max.is_zero() {
+        return Decimal::zero();
+    }
+    decimal_division_in_256(value, max)
+}
+
+fn one_minus(value: Decimal) -> Decimal {
+    decimal_subtraction_in_256(Decimal::one(), cap_at_one(value))
+}
+
+fn cap_at_one(value: Decimal) -> Decimal {
+    value.min(Decimal::one())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs(addr: &str, apr: u64, commission: u64) -> ScoreInputs {
+        ScoreInputs {
+            addr: Addr::unchecked(addr),
+            apr: Decimal::percent(apr),
+            commission: Decimal::percent(commission),
+            max_commission: Decimal::percent(20),
+            self_delegation_ratio: Decimal::one(),
+            slashing_ratio: Decimal::one(),
+            recorded_ratio: Decimal::one(),
+        }
+    }
+
+    #[test]
+    fn test_compute_scores() {
+        let weights = ScoreWeights {
+            apr: Decimal::one(),
+            commission: Decimal::one(),
+            max_commission: Decimal::zero(),
+            self_delegation: Decimal::zero(),
+            slashing: Decimal::zero(),
+            recording: Decimal::zero(),
+        };
+        let scores = compute_scores(
+            vec![
+                inputs("a1", 5, 10),
+                inputs("a2", 10, 10),
+                inputs("a3", 10, 50),
+            ],
+            &weights,
+        );
+
+        let ranking: Vec<&str> = scores.iter().map(|s| s.addr.as_str()).collect();
+        assert_eq!(ranking, vec!["a2", "a3", "a1"]);
+        // (1 + 0.9) / 2
+        assert_eq!(scores[0].score, Decimal::percent(95));
+        // (0.5 + 0.9) / 2
+        assert_eq!(scores[2].score, Decimal::percent(70));
+    }
+
+    #[test]
+    fn test_compute_scores_penalizes_slashing() {
+        let mut slashed = inputs("a1", 10, 10);
+        slashed.slashing_ratio = Decimal::percent(95);
+        let scores = compute_scores(
+            vec![slashed, inputs("a2", 10, 10)],
+            &ScoreWeights::default(),
+        );
+        assert_eq!(scores[0].addr, Addr::unchecked("a2"));
+        assert!(scores[0].score > scores[1].score);
+    }
+}
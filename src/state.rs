@@ -3,18 +3,27 @@ use serde::{Deserialize, Serialize};
 
 use crate::constants;
 use cosmwasm_std::{Addr, Coin, Decimal, Uint128};
-use cw_storage_plus::{Item, Map, U16Key, U64Key};
+use cw_storage_plus::{
+    Index, IndexList, IndexedMap, Item, Map, MultiIndex, U16Key, U64Key, UniqueIndex,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
     pub vault_denom: String,
-    // note: querying validator by address is costly, as it requires iteration, can
-    // be optimized with a cleaner way to query, but might not be important, as number of validators
-    // is not expected to grow beyond 20-30.
-    pub validators: Vec<ValidatorAccounts>,
     //hard to remove from this, costs O(T) time, if was a set, could be O(1) average time
     pub cron_timestamps: Vec<u64>,
+    // insertion order of the next validator to be recorded in the current cron
     pub validator_index_for_next_cron: u64,
+    // insertion order assigned to the next validator added to the registry
+    #[serde(default)]
+    pub next_validator_insertion_order: u64,
+    #[serde(default)]
+    pub total_validators: u64,
+    #[serde(default)]
+    pub next_unbonding_id: u64,
+    // sum of the unbonding ledger, tokens that are neither staked nor in the contract balance yet
+    #[serde(default)]
+    pub total_unbonding: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -27,12 +36,130 @@ pub struct Config {
     pub manager: Addr,
     pub amount_to_stake_per_validator: Uint128,
     pub batch_size: u64,
+    pub pending_manager: Option<PendingManager>,
+    #[serde(default)]
+    pub score_weights: ScoreWeights,
+    // commission changes above this are flagged
+    #[serde(default = "default_commission_change_threshold")]
+    pub commission_change_threshold: Decimal,
+    // when set, RecordMetrics for a new timestamp fails until the previous cron is complete
+    #[serde(default)]
+    pub refuse_new_cron_when_incomplete: bool,
+    // when set, cron timestamps are derived from the block time instead of trusting the caller
+    #[serde(default)]
+    pub cron_schedule: Option<CronSchedule>,
+    // when set, anyone can record metrics once the cron interval has elapsed and gets tipped for it
+    #[serde(default)]
+    pub keeper_incentive: Option<KeeperIncentive>,
+    // the staking querier doesn't expose the chain's unbonding time
+    #[serde(default = "default_unbonding_period")]
+    pub unbonding_period: u64, // in seconds
+}
+
+pub fn default_unbonding_period() -> u64 {
+    21 * 24 * 60 * 60
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct KeeperIncentive {
+    pub tip_per_batch: Uint128, // in vault denom, paid from the contract balance
+    pub max_tipped_batches_per_cron: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CronSchedule {
+    pub interval: u64, // in seconds, block time is rounded down to it (86400 is midnight UTC)
+    pub tolerance: u64, // in seconds, how far a supplied timestamp may be from the block time
+}
+
+pub fn default_commission_change_threshold() -> Decimal {
+    Decimal::percent(1)
+}
+
+// Relative weights of each component of the validator score, they don't need to add up to one
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScoreWeights {
+    pub apr: Decimal,
+    pub commission: Decimal,
+    pub max_commission: Decimal,
+    pub self_delegation: Decimal,
+    pub slashing: Decimal,
+    pub recording: Decimal, // share of crons the validator was recorded in
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        ScoreWeights {
+            apr: Decimal::percent(40),
+            commission: Decimal::percent(20),
+            max_commission: Decimal::percent(10),
+            self_delegation: Decimal::percent(10),
+            slashing: Decimal::percent(10),
+            recording: Decimal::percent(10),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingManager {
+    pub addr: Addr,
+    pub expires_at: Option<u64>, // block time in seconds, proposal never expires if not set
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Admin,           // manage validators, config and roles
+    MetricsRecorder, // record on chain metrics
+    OffChainPublisher,
+    Pruner,    // delete metrics and timestamps
+    Treasurer, // withdraw funds to allowed recipients
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::MetricsRecorder => "metrics_recorder",
+            Role::OffChainPublisher => "off_chain_publisher",
+            Role::Pruner => "pruner",
+            Role::Treasurer => "treasurer",
+        }
+    }
+
+    pub fn all() -> Vec<Role> {
+        vec![
+            Role::Admin,
+            Role::MetricsRecorder,
+            Role::OffChainPublisher,
+            Role::Pruner,
+            Role::Treasurer,
+        ]
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ValidatorAccounts {
     pub operator_address: Addr,
     pub account_address: Addr,
+    pub insertion_order: u64,
+    // principal delegated by the contract, restaked rewards are not included
+    #[serde(default)]
+    pub staked_amount: Uint128,
+}
+
+// Validators used to live in `State.validators`, this is only read by migrate to move them
+// into the validator registry.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyState {
+    #[serde(default)]
+    pub validators: Vec<LegacyValidatorAccounts>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyValidatorAccounts {
+    pub operator_address: Addr,
+    pub account_address: Addr,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -46,6 +173,113 @@ pub struct ValidatorMetrics {
     pub max_commission: Decimal,
     pub timestamp: u64,
     pub rewards_in_coins: Vec<Coin>,
+    // the staking querier does not expose validator tokens, these are unset until RecordVotingPower
+    #[serde(default)]
+    pub voting_power: Option<Uint128>, // total tokens bonded to the validator
+    #[serde(default)]
+    pub network_share: Option<Decimal>, // voting_power / total bonded tokens of the network
+    // block at which the data was read, zero for metrics recorded before it was tracked
+    #[serde(default)]
+    pub block_height: u64,
+    #[serde(default)]
+    pub block_time: u64, // in seconds
+    // accumulated rewards went down since the previous record (withdrawn outside of ClaimRewards,
+    // e.g. by a redelegation), the whole accumulated value was taken as earned since then
+    #[serde(default)]
+    pub rewards_reset: bool,
+}
+
+// Claiming rewards or changing our delegation resets the accumulated rewards of our delegation.
+// Until the next cron the rewards since the previous cron are diffed against this instead of the
+// previous metrics.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardsBaseline {
+    pub coins: Vec<Coin>,   // accumulated rewards right after the last claim
+    pub claimed: Vec<Coin>, // rewards claimed since the previous cron, on top of its rewards_in_coins
+    pub restaked: Uint128,  // claimed rewards delegated back since the previous cron
+    // principal delegated by TopUpStake and undelegated by ReduceStake since the previous cron
+    #[serde(default)]
+    pub delegated: Uint128,
+    #[serde(default)]
+    pub undelegated: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondingEntry {
+    pub id: u64,
+    pub validator: Addr,
+    pub amount: Coin,
+    pub started_at: u64,   // block time in seconds
+    pub completes_at: u64, // expected, from the configured unbonding period
+}
+
+// The staking querier only returns validators in the active set, so jailed validators can't be
+// told apart from unbonded ones directly. A validator that left the set while our delegation got
+// slashed is taken as jailed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidatorStatus {
+    Active,
+    Jailed,
+    MissingFromSet,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ValidatorStatusRecord {
+    pub timestamp: u64,
+    pub status: ValidatorStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SlashingEvent {
+    pub validator: Addr,
+    pub timestamp: u64,       // cron timestamp the slashing was detected at
+    pub ratio: Decimal,       // current slashing_pointer / previous slashing_pointer
+    pub amount_lost: Uint128, // drop in our delegation since the previous record
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SkippedValidator {
+    pub addr: Addr,
+    pub reason: ValidatorStatus,
+}
+
+// Progress of a cron across RecordMetrics batches
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CronRecord {
+    pub timestamp: u64,
+    pub total_validators: u64, // when the cron started
+    pub recorded: u64,
+    pub skipped: Vec<SkippedValidator>,
+    pub started_at_height: u64,
+    pub started_at_time: u64,            // block time in seconds
+    pub finished_at_height: Option<u64>, // set once every validator has been processed
+    pub finished_at_time: Option<u64>,
+    pub next_insertion_order: u64, // insertion order of the next validator to record
+    pub tipped_batches: u64,       // batches recorded by keepers that were paid a tip
+}
+
+// Summary of the validators recorded for a cron, updated as each batch is recorded
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct TimestampAggregate {
+    pub timestamp: u64,
+    pub validators_recorded: u64,
+    // apr against the previous record of each validator that has one, the median is computed on query
+    pub apr_count: u64,
+    pub apr_sum: Decimal,
+    pub min_apr: Option<Decimal>,
+    pub max_apr: Option<Decimal>,
+    pub total_delegated: Uint128,
+    pub total_rewards: Decimal,
+    pub stake_weighted_commission_sum: Decimal, // sum of commission * delegated_amount
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CommissionChange {
+    pub validator: Addr,
+    pub timestamp: u64, // cron timestamp the change was detected at
+    pub previous_commission: Decimal,
+    pub new_commission: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -75,10 +309,113 @@ pub struct OffChainValidatorMetrics {
 pub const METRICS_HISTORY: Map<(&Addr, U64Key), ValidatorMetrics> =
     Map::new("validator_metrics_history");
 
+// (Validator Addr, Timestamp), written for every validator in every cron
+pub const STATUS_HISTORY: Map<(&Addr, U64Key), ValidatorStatusRecord> =
+    Map::new("validator_status_history");
+
+// Unbonding id, entries are removed by SweepMaturedUnbondings once they complete
+pub const UNBONDINGS: Map<U64Key, UnbondingEntry> = Map::new("unbondings");
+
+// Addresses funds can be withdrawn to, besides the manager
+pub const WITHDRAWAL_RECIPIENTS: Map<&Addr, bool> = Map::new("withdrawal_recipients");
+
+// Validator Addr, removed once the next cron records the validator
+pub const REWARDS_BASELINES: Map<&Addr, RewardsBaseline> = Map::new("rewards_baselines");
+
+// Timestamp
+pub const CRON_RECORDS: Map<U64Key, CronRecord> = Map::new("cron_records");
+
+// Timestamp
+pub const TIMESTAMP_AGGREGATES: Map<U64Key, TimestampAggregate> = Map::new("timestamp_aggregates");
+
 pub const STATE: Item<State> = Item::new("state");
 
+pub(crate) const LEGACY_STATE: Item<LegacyState> = Item::new("state");
+
 pub const CONFIG: Item<Config> = Item::new("config");
 
+// (Role, Address)
+pub const ROLES: Map<(&str, &Addr), bool> = Map::new("roles");
+
+pub struct ValidatorIndexes<'a> {
+    // (account address, operator address)
+    pub account: MultiIndex<'a, (Vec<u8>, Vec<u8>), ValidatorAccounts>,
+    pub insertion_order: UniqueIndex<'a, U64Key, ValidatorAccounts>,
+}
+
+impl<'a> IndexList<ValidatorAccounts> for ValidatorIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<ValidatorAccounts>> + '_> {
+        let v: Vec<&dyn Index<ValidatorAccounts>> = vec![&self.account, &self.insertion_order];
+        Box::new(v.into_iter())
+    }
+}
+
+// Validator registry keyed by operator address
+pub fn validators<'a>() -> IndexedMap<'a, &'a Addr, ValidatorAccounts, ValidatorIndexes<'a>> {
+    let indexes = ValidatorIndexes {
+        account: MultiIndex::new(
+            |v, pk| (v.account_address.as_bytes().to_vec(), pk),
+            constants::VALIDATORS,
+            constants::VALIDATORS_ACCOUNT_IDX,
+        ),
+        insertion_order: UniqueIndex::new(
+            |v| U64Key::new(v.insertion_order),
+            constants::VALIDATORS_INSERTION_ORDER_IDX,
+        ),
+    };
+    IndexedMap::new(constants::VALIDATORS, indexes)
+}
+
+pub struct SlashingEventIndexes<'a> {
+    // (validator operator address, pk)
+    pub validator: MultiIndex<'a, (Vec<u8>, Vec<u8>), SlashingEvent>,
+}
+
+impl<'a> IndexList<SlashingEvent> for SlashingEventIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<SlashingEvent>> + '_> {
+        let v: Vec<&dyn Index<SlashingEvent>> = vec![&self.validator];
+        Box::new(v.into_iter())
+    }
+}
+
+// (Timestamp, Validator Addr)
+pub fn slashing_events<'a>(
+) -> IndexedMap<'a, (U64Key, &'a Addr), SlashingEvent, SlashingEventIndexes<'a>> {
+    let indexes = SlashingEventIndexes {
+        validator: MultiIndex::new(
+            |e, pk| (e.validator.as_bytes().to_vec(), pk),
+            constants::SLASHING_EVENTS,
+            constants::SLASHING_EVENTS_VALIDATOR_IDX,
+        ),
+    };
+    IndexedMap::new(constants::SLASHING_EVENTS, indexes)
+}
+
+pub struct CommissionChangeIndexes<'a> {
+    // (validator operator address, pk)
+    pub validator: MultiIndex<'a, (Vec<u8>, Vec<u8>), CommissionChange>,
+}
+
+impl<'a> IndexList<CommissionChange> for CommissionChangeIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<CommissionChange>> + '_> {
+        let v: Vec<&dyn Index<CommissionChange>> = vec![&self.validator];
+        Box::new(v.into_iter())
+    }
+}
+
+// (Timestamp, Validator Addr)
+pub fn commission_changes<'a>(
+) -> IndexedMap<'a, (U64Key, &'a Addr), CommissionChange, CommissionChangeIndexes<'a>> {
+    let indexes = CommissionChangeIndexes {
+        validator: MultiIndex::new(
+            |c, pk| (c.validator.as_bytes().to_vec(), pk),
+            constants::COMMISSION_CHANGES,
+            constants::COMMISSION_CHANGES_VALIDATOR_IDX,
+        ),
+    };
+    IndexedMap::new(constants::COMMISSION_CHANGES, indexes)
+}
+
 // off chain details
 
 pub const OFF_CHAIN_STATE: Item<OffChainState> = Item::new(constants::OFF_CHAIN_STATE);
@@ -71,36 +71,230 @@ pub fn compute_apr(
     Ok(decimal_division_in_256(numerator, denominator))
 }
 
+// Principal lost to slashing between h1 and h2, derived from the ratio of slashing pointers.
+// The pointer only moves down on slashing, so a ratio >= 1 means nothing was lost.
+pub fn compute_principal_lost_to_slashing(h1: &ValidatorMetrics, h2: &ValidatorMetrics) -> Decimal {
+    if h1.slashing_pointer.is_zero() || h2.slashing_pointer >= h1.slashing_pointer {
+        return Decimal::zero();
+    }
+
+    let slashing_ratio = decimal_division_in_256(h2.slashing_pointer, h1.slashing_pointer);
+    decimal_multiplication_in_256(
+        uint128_to_decimal(h1.delegated_amount),
+        decimal_subtraction_in_256(Decimal::one(), slashing_ratio),
+    )
+}
+
+// Same as compute_apr, but the principal lost to slashing is subtracted from the rewards.
+// Decimals are unsigned, so a validator that lost more than it earned has an apr of zero.
+pub fn compute_slashing_adjusted_apr(
+    h1: &ValidatorMetrics,
+    h2: &ValidatorMetrics,
+    time_diff_in_seconds: u64,
+) -> StdResult<Decimal> {
+    let apr = compute_apr(h1, h2, time_diff_in_seconds)?;
+    let principal_lost = compute_principal_lost_to_slashing(h1, h2);
+    if principal_lost.is_zero() {
+        return Ok(apr);
+    }
+
+    let apr_lost = decimal_division_in_256(
+        decimal_multiplication_in_256(principal_lost, u64_to_decimal(3153600000)),
+        decimal_multiplication_in_256(
+            uint128_to_decimal(h1.delegated_amount),
+            u64_to_decimal(time_diff_in_seconds),
+        ),
+    );
+
+    if apr_lost >= apr {
+        return Ok(Decimal::zero());
+    }
+    Ok(decimal_subtraction_in_256(apr, apr_lost))
+}
+
+// Each recorded commission is weighted by the time until the next record.
+// `metrics` is expected to be sorted by timestamp.
+pub fn compute_time_weighted_commission(metrics: &[ValidatorMetrics]) -> StdResult<Decimal> {
+    if metrics.is_empty() {
+        return Err(StdError::GenericErr {
+            msg: "Cannot compute commission without any metrics".to_string(),
+        });
+    }
+
+    let first = metrics.first().unwrap();
+    let last = metrics.last().unwrap();
+    let total_time = last.timestamp - first.timestamp;
+    if total_time == 0 {
+        return Ok(first.commission);
+    }
+
+    let mut weighted_commission = Decimal::zero();
+    for window in metrics.windows(2) {
+        let time_diff = window[1].timestamp - window[0].timestamp;
+        weighted_commission = decimal_summation_in_256(
+            weighted_commission,
+            decimal_multiplication_in_256(window[0].commission, u64_to_decimal(time_diff)),
+        );
+    }
+
+    Ok(decimal_division_in_256(
+        weighted_commission,
+        u64_to_decimal(total_time),
+    ))
+}
+
+// Rewards on our delegation are already net of commission, so gross = net / (1 - commission).
+// With a 100% commission nothing reaches delegators and the gross apr cannot be derived.
+pub fn compute_gross_apr(net_apr: Decimal, commission: Decimal) -> Option<Decimal> {
+    if commission >= Decimal::one() {
+        return None;
+    }
+    Some(decimal_division_in_256(
+        net_apr,
+        decimal_subtraction_in_256(Decimal::one(), commission),
+    ))
+}
+
+// `values` is expected to be sorted
+pub fn compute_median(values: &[Decimal]) -> Option<Decimal> {
+    let len = values.len();
+    if len == 0 {
+        return None;
+    }
+    if len % 2 == 1 {
+        return Some(values[len / 2]);
+    }
+    Some(decimal_division_in_256(
+        decimal_summation_in_256(values[len / 2 - 1], values[len / 2]),
+        u64_to_decimal(2),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use cosmwasm_std::Addr;
 
     use super::*;
 
+    fn test_metric(timestamp: u64) -> ValidatorMetrics {
+        ValidatorMetrics {
+            operator_addr: Addr::unchecked("a1"),
+            rewards: Decimal::zero(),
+            slashing_pointer: Decimal::one(),
+            delegated_amount: Uint128::new(100),
+            self_delegated_amount: Uint128::new(5),
+            commission: Decimal::zero(),
+            max_commission: Decimal::one(),
+            timestamp,
+            rewards_in_coins: vec![],
+            voting_power: Default::default(),
+            network_share: Default::default(),
+            block_height: Default::default(),
+            block_time: Default::default(),
+            rewards_reset: false,
+        }
+    }
+
     #[test]
     fn test_compute_apr() {
         let h1 = ValidatorMetrics {
-            operator_addr: Addr::unchecked("a1"),
             rewards: Decimal::one(),
-            slashing_pointer: Decimal::percent(90),
             delegated_amount: Uint128::new(10),
-            self_delegated_amount: Uint128::new(5),
-            commission: Decimal::one(),
-            max_commission: Decimal::one(),
-            timestamp: 1,
-            rewards_in_coins: vec![],
+            ..test_metric(1)
         };
         let h2 = ValidatorMetrics {
-            operator_addr: Addr::unchecked("a1"),
             rewards: u64_to_decimal(2),
-            slashing_pointer: Decimal::percent(98),
-            delegated_amount: Uint128::new(100),
-            self_delegated_amount: Uint128::new(10),
-            commission: Decimal::one(),
-            max_commission: Decimal::one(),
-            timestamp: 2,
-            rewards_in_coins: vec![],
+            ..test_metric(2)
         };
         assert_eq!(compute_apr(&h1, &h2, 1), Ok(u64_to_decimal(315360000)))
     }
+
+    #[test]
+    fn test_compute_slashing_adjusted_apr() {
+        let metric = |rewards: u64, slashing_pointer: Decimal, timestamp: u64| ValidatorMetrics {
+            rewards: u64_to_decimal(rewards),
+            slashing_pointer,
+            ..test_metric(timestamp)
+        };
+        let time_diff = 31536000; // one year
+        let h1 = metric(0, Decimal::one(), 0);
+
+        // 10% rewards, no slashing
+        let h2 = metric(10, Decimal::one(), time_diff);
+        assert_eq!(
+            compute_principal_lost_to_slashing(&h1, &h2),
+            Decimal::zero()
+        );
+        assert_eq!(
+            compute_slashing_adjusted_apr(&h1, &h2, time_diff),
+            Ok(u64_to_decimal(10))
+        );
+
+        // 10% rewards, 5% slashed
+        let h2 = metric(10, Decimal::percent(95), time_diff);
+        assert_eq!(
+            compute_principal_lost_to_slashing(&h1, &h2),
+            u64_to_decimal(5)
+        );
+        assert_eq!(
+            compute_slashing_adjusted_apr(&h1, &h2, time_diff),
+            Ok(u64_to_decimal(5))
+        );
+
+        // slashed more than earned
+        let h2 = metric(10, Decimal::percent(50), time_diff);
+        assert_eq!(
+            compute_slashing_adjusted_apr(&h1, &h2, time_diff),
+            Ok(Decimal::zero())
+        );
+    }
+
+    #[test]
+    fn test_compute_time_weighted_commission() {
+        let metric = |commission: Decimal, timestamp: u64| ValidatorMetrics {
+            commission,
+            ..test_metric(timestamp)
+        };
+        // 5% for 3 seconds, 10% for 1 second
+        let metrics = vec![
+            metric(Decimal::percent(5), 0),
+            metric(Decimal::percent(5), 2),
+            metric(Decimal::percent(10), 3),
+            metric(Decimal::percent(20), 4),
+        ];
+        assert_eq!(
+            compute_time_weighted_commission(&metrics),
+            Ok(Decimal::from_ratio(25_u128, 400_u128))
+        );
+        assert_eq!(
+            compute_time_weighted_commission(&metrics[..1]),
+            Ok(Decimal::percent(5))
+        );
+    }
+
+    #[test]
+    fn test_compute_gross_apr() {
+        assert_eq!(
+            compute_gross_apr(Decimal::percent(9), Decimal::percent(10)),
+            Some(Decimal::percent(10))
+        );
+        assert_eq!(compute_gross_apr(Decimal::percent(9), Decimal::one()), None);
+    }
+
+    #[test]
+    fn test_compute_median() {
+        assert_eq!(compute_median(&[]), None);
+        assert_eq!(
+            compute_median(&[
+                Decimal::percent(1),
+                Decimal::percent(5),
+                Decimal::percent(7)
+            ]),
+            Some(Decimal::percent(5))
+        );
+        assert_eq!(
+            compute_median(&[Decimal::percent(1), Decimal::percent(5)]),
+            Some(Decimal::percent(3))
+        );
+    }
 }